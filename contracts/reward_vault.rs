@@ -1,8 +1,16 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::log::sol_log_compute_units;
 use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::program_pack::Pack;
 use anchor_lang::solana_program::system_instruction;
-use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use anchor_spl::associated_token::{self, get_associated_token_address, get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token::{self, spl_token, Mint, SyncNative, Token, TokenAccount, Transfer, TransferChecked};
+use anchor_spl::token_interface::{self, TokenInterface};
 
 // Program ID - This would be replaced with actual deployed program ID
 declare_id!("3AVRxMyR7ci4LiYbLKKG8zKisfaSNiDF9WqdYP65kkhF");
@@ -36,24 +44,142 @@ pub mod reward_vault {
      * @param distributor - Public key of the authorized distributor
      * @param reward_mint - Optional SPL token mint (required if not paying SOL)
      * @param pay_sol - Whether to pay rewards in SOL or SPL tokens
+     * @param min_disbursement - Smallest allowed disbursement (0 = any non-zero amount)
+     * @param max_epochs - Maximum number of epochs the vault may create (0 = unlimited)
+     * @param metadata - UTF-8 display name and metadata URI for front-ends
      */
     pub fn init_vault(
         ctx: Context<InitVault>,
         distributor: Pubkey,
         reward_mint: Option<Pubkey>,
         pay_sol: bool,
+        min_disbursement: u64,
+        max_epochs: u64,
+        metadata: VaultMetadata,
     ) -> Result<()> {
         // Validate that either SOL payment is enabled or SPL mint is provided
         require!(pay_sol || reward_mint.is_some(), RewardVaultError::RewardMintRequired);
 
+        let vault_init = VaultInit {
+            admin: ctx.accounts.admin.key(),
+            distributor,
+            reward_mint,
+            pay_sol,
+            min_disbursement,
+            max_epochs,
+            name: encode_metadata(&metadata.name)?,
+            metadata_uri: encode_metadata(&metadata.metadata_uri)?,
+        };
+
+        // Initialize vault with provided parameters
+        let mint_decimals = resolve_mint_decimals(pay_sol, reward_mint, ctx.accounts.mint.as_ref())?;
         let reward_vault = &mut ctx.accounts.reward_vault;
-        
+        initialize_vault(reward_vault, &vault_init, mint_decimals, ctx.bumps.reward_vault);
+
+        Ok(())
+    }
+
+    /**
+     * Initialize a reward vault if it does not already exist
+     * 
+     * Idempotent variant of init_vault for deployment scripts. A fresh vault
+     * is initialized as usual; an existing vault is left untouched as long as
+     * every configured field, metadata included, matches the requested
+     * parameters.
+     * 
+     * @param ctx - Context containing vault initialization accounts
+     * @param distributor - Public key of the authorized distributor
+     * @param reward_mint - Optional SPL token mint (required if not paying SOL)
+     * @param pay_sol - Whether to pay rewards in SOL or SPL tokens
+     * @param min_disbursement - Smallest allowed disbursement (0 = any non-zero amount)
+     * @param max_epochs - Maximum number of epochs the vault may create (0 = unlimited)
+     * @param metadata - UTF-8 display name and metadata URI for front-ends
+     */
+    pub fn init_vault_if_needed(
+        ctx: Context<InitVaultIfNeeded>,
+        distributor: Pubkey,
+        reward_mint: Option<Pubkey>,
+        pay_sol: bool,
+        min_disbursement: u64,
+        max_epochs: u64,
+        metadata: VaultMetadata,
+    ) -> Result<()> {
+        require!(pay_sol || reward_mint.is_some(), RewardVaultError::RewardMintRequired);
+
+        let vault_init = VaultInit {
+            admin: ctx.accounts.admin.key(),
+            distributor,
+            reward_mint,
+            pay_sol,
+            min_disbursement,
+            max_epochs,
+            name: encode_metadata(&metadata.name)?,
+            metadata_uri: encode_metadata(&metadata.metadata_uri)?,
+        };
+
+        // An existing vault must match the requested configuration exactly
+        if ctx.accounts.reward_vault.admin != Pubkey::default() {
+            require!(
+                vault_init.matches(&ctx.accounts.reward_vault),
+                RewardVaultError::VaultAlreadyInitialized
+            );
+            return Ok(());
+        }
+
         // Initialize vault with provided parameters
-        reward_vault.admin = ctx.accounts.admin.key();
-        reward_vault.distributor = distributor;
-        reward_vault.reward_mint = reward_mint;
+        let mint_decimals = resolve_mint_decimals(pay_sol, reward_mint, ctx.accounts.mint.as_ref())?;
+        let reward_vault = &mut ctx.accounts.reward_vault;
+        initialize_vault(reward_vault, &vault_init, mint_decimals, ctx.bumps.reward_vault);
+
+        Ok(())
+    }
+
+    /**
+     * Reconfigure vault payout mode
+     * 
+     * Switches a vault between SOL and SPL payouts without recreating the
+     * account. Only allowed once the balance held for the current mode is
+     * empty, so no funds are stranded by the switch.
+     * 
+     * @param ctx - Context containing admin and vault accounts
+     * @param pay_sol - Whether to pay rewards in SOL or SPL tokens
+     * @param reward_mint - Optional SPL token mint (required if not paying SOL)
+     */
+    pub fn reconfigure_payout(
+        ctx: Context<ReconfigurePayout>,
+        pay_sol: bool,
+        reward_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        // Apply the same configuration rules as init_vault
+        require!(pay_sol || reward_mint.is_some(), RewardVaultError::RewardMintRequired);
+
+        // Ensure the balance backing the current mode has been drained
+        ensure_vault_empty(&ctx.accounts.reward_vault, ctx.accounts.vault_token_account.as_ref())?;
+
+        let mint_decimals = resolve_mint_decimals(pay_sol, reward_mint, ctx.accounts.mint.as_ref())?;
+
+        let reward_vault = &mut ctx.accounts.reward_vault;
+        let old_pay_sol = reward_vault.pay_sol;
         reward_vault.pay_sol = pay_sol;
-        reward_vault.bump = ctx.bumps.reward_vault;
+        reward_vault.reward_mint = reward_mint;
+        reward_vault.mint_decimals = mint_decimals;
+        reward_vault.refresh_config_hash();
+
+        emit!(PayoutReconfigured {
+            vault: reward_vault.key(),
+            pay_sol,
+            reward_mint,
+        });
+
+        // Flag an actual SOL/SPL switch separately from a mint change
+        if old_pay_sol != pay_sol {
+            emit!(PayoutModeChanged {
+                vault: reward_vault.key(),
+                old_pay_sol,
+                new_pay_sol: pay_sol,
+                reward_mint,
+            });
+        }
 
         Ok(())
     }
@@ -68,25 +194,37 @@ pub mod reward_vault {
      * @param start_ts - Unix timestamp for epoch start
      * @param end_ts - Unix timestamp for epoch end
      * @param index - Sequential epoch index number
+     * @param allowlist_root - Merkle root of allowed recipients (zeroed = open)
+     * @param claim_grace_seconds - Seconds after end_ts before funds can be swept
+     * @param epoch_distributor - Optional distributor overriding the vault's for this epoch
      */
     pub fn start_epoch(
         ctx: Context<StartEpoch>,
         start_ts: i64,
         end_ts: i64,
         index: u64,
+        allowlist_root: [u8; 32],
+        claim_grace_seconds: i64,
+        epoch_distributor: Option<Pubkey>,
     ) -> Result<()> {
-        // Validate epoch time window
-        require!(start_ts < end_ts, RewardVaultError::InvalidEpochWindow);
+        let spec = EpochSpec {
+            start_ts,
+            end_ts,
+            index,
+            allowlist_root,
+            claim_grace_seconds,
+            epoch_distributor,
+        };
+        let now = Clock::get()?.unix_timestamp;
+        validate_epoch_spec(&ctx.accounts.reward_vault, &spec, now)?;
 
-        let epoch = &mut ctx.accounts.epoch;
-        
         // Initialize epoch with provided parameters
-        epoch.vault = ctx.accounts.reward_vault.key();
-        epoch.start_ts = start_ts;
-        epoch.end_ts = end_ts;
-        epoch.index = index;
-        epoch.total_funded = 0;
-        epoch.bump = ctx.bumps.epoch;
+        let epoch = new_epoch(ctx.accounts.reward_vault.key(), &spec, ctx.bumps.epoch);
+        ctx.accounts.epoch.set_inner(epoch);
+        record_epoch_window(&mut ctx.accounts.reward_vault, end_ts);
+        if let Some(vault_stats) = active_vault_stats(&ctx.accounts.reward_vault, ctx.accounts.vault_stats.as_mut())? {
+            vault_stats.record_epochs(1)?;
+        }
 
         // Emit event for off-chain tracking
         emit!(NewEpoch {
@@ -98,6 +236,41 @@ pub mod reward_vault {
         Ok(())
     }
 
+    /**
+     * Extend an epoch
+     * 
+     * Pushes back the end of a running epoch, e.g. when a tournament runs
+     * long. Epochs can only be lengthened, and only while still running.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     * @param new_end_ts - New unix timestamp for epoch end
+     */
+    pub fn extend_epoch(ctx: Context<UpdateEpoch>, new_end_ts: i64) -> Result<()> {
+        let epoch = &mut ctx.accounts.epoch;
+        let old_end = epoch.end_ts;
+
+        // Validate the extension only ever lengthens a live epoch
+        require!(new_end_ts > old_end, RewardVaultError::CannotShortenEpoch);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < old_end, RewardVaultError::EpochAlreadyEnded);
+
+        // With ordered epochs, only the latest epoch may be extended
+        let reward_vault = &mut ctx.accounts.reward_vault;
+        if reward_vault.non_overlapping_epochs {
+            require!(old_end >= reward_vault.last_epoch_end, RewardVaultError::OverlappingEpoch);
+        }
+        reward_vault.last_epoch_end = reward_vault.last_epoch_end.max(new_end_ts);
+
+        epoch.end_ts = new_end_ts;
+
+        emit!(EpochExtended {
+            old_end,
+            new_end: new_end_ts,
+        });
+
+        Ok(())
+    }
+
     /**
      * Fund the reward vault
      * 
@@ -106,28 +279,104 @@ pub mod reward_vault {
      * 
      * @param ctx - Context containing funding accounts
      * @param amount - Amount to fund in lamports or token units
+     * @param proof - Merkle proof of the funder against the vault funder allowlist
+     * @param idempotency_key - Optional client key; a retry with the same key is a no-op
      */
-    pub fn fund_vault(ctx: Context<FundVault>, amount: u64) -> Result<()> {
+    pub fn fund_vault(
+        ctx: Context<FundVault>,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+        idempotency_key: Option<[u8; 16]>,
+    ) -> Result<()> {
         require!(amount > 0, RewardVaultError::InvalidAmount);
+        require!(amount <= RewardVault::MAX_SINGLE_FUND, RewardVaultError::AmountTooLarge);
+
+        // A receipt for this key means the funding already went through
+        if let Some(idempotency_key) = idempotency_key {
+            if !create_fund_receipt(&ctx, idempotency_key, amount)? {
+                return Ok(());
+            }
+        }
 
         let reward_vault = &ctx.accounts.reward_vault;
 
-        // Route funding based on vault configuration
-        if reward_vault.pay_sol {
-            fund_sol(&ctx, amount)?;
+        // Enforce the funder allowlist if one is configured
+        require!(
+            is_allowlisted(reward_vault.funder_allowlist_root, ctx.accounts.funder.key(), &proof),
+            RewardVaultError::FunderNotAllowed
+        );
+
+        // Route funding based on vault configuration, tracking what actually arrived
+        let received = if reward_vault.pay_sol {
+            fund_sol(&ctx, amount)?
         } else {
-            fund_spl(&ctx, amount)?;
+            fund_spl(&ctx, amount)?
+        };
+        record_funder_contribution(&ctx, received)?;
+        if let Some(vault_stats) = active_vault_stats(&ctx.accounts.reward_vault, ctx.accounts.vault_stats.as_mut())? {
+            vault_stats.record_funding(received)?;
         }
 
         // Update epoch tracking if provided
-        if let Some(epoch) = ctx.accounts.epoch.as_mut() {
-            require_keys_eq!(epoch.vault, reward_vault.key(), RewardVaultError::EpochMismatch);
-            
-            // Safely add to total funded with overflow protection
-            epoch.total_funded = epoch
-                .total_funded
-                .checked_add(amount as u128)
-                .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        let vault_key = reward_vault.key();
+        record_epoch_funding(ctx.accounts.epoch.as_mut(), vault_key, received)?;
+
+        if ctx.accounts.reward_vault.emit_events {
+            emit!(VaultFunded {
+                vault: vault_key,
+                funder: ctx.accounts.funder.key(),
+                amount: received,
+                vault_balance_after: vault_balance_after(&ctx.accounts.reward_vault, ctx.accounts.vault_token_account.as_ref().map(AsRef::as_ref))?,
+            });
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Fund a wrapped-SOL vault with native SOL
+     * 
+     * Convenience for SPL vaults whose reward mint is wSOL: transfers SOL
+     * straight into the vault's wSOL token account and syncs its native
+     * balance, so funders don't have to wrap SOL themselves.
+     * 
+     * @param ctx - Context containing funding accounts
+     * @param amount - Amount to fund in lamports
+     * @param proof - Merkle proof of the funder against the vault funder allowlist
+     */
+    pub fn fund_wrap_sol(ctx: Context<FundVault>, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+        require!(amount > 0, RewardVaultError::InvalidAmount);
+        require!(amount <= RewardVault::MAX_SINGLE_FUND, RewardVaultError::AmountTooLarge);
+
+        let reward_vault = &ctx.accounts.reward_vault;
+        require!(
+            !reward_vault.pay_sol && reward_vault.reward_mint == Some(spl_token::native_mint::ID),
+            RewardVaultError::NotWrappedSol
+        );
+
+        // Enforce the funder allowlist if one is configured
+        require!(
+            is_allowlisted(reward_vault.funder_allowlist_root, ctx.accounts.funder.key(), &proof),
+            RewardVaultError::FunderNotAllowed
+        );
+
+        fund_wrapped_sol(&ctx, amount)?;
+        record_funder_contribution(&ctx, amount)?;
+        if let Some(vault_stats) = active_vault_stats(&ctx.accounts.reward_vault, ctx.accounts.vault_stats.as_mut())? {
+            vault_stats.record_funding(amount)?;
+        }
+
+        // Update epoch tracking if provided
+        let vault_key = reward_vault.key();
+        record_epoch_funding(ctx.accounts.epoch.as_mut(), vault_key, amount)?;
+
+        if ctx.accounts.reward_vault.emit_events {
+            emit!(VaultFunded {
+                vault: vault_key,
+                funder: ctx.accounts.funder.key(),
+                amount,
+                vault_balance_after: vault_balance_after(&ctx.accounts.reward_vault, ctx.accounts.vault_token_account.as_ref().map(AsRef::as_ref))?,
+            });
         }
 
         Ok(())
@@ -138,21 +387,86 @@ pub mod reward_vault {
      * 
      * Distributes SOL from the vault to a recipient. Only authorized distributors
      * can call this function, and the vault must be configured for SOL payments.
+     * Recipients are wallets by default; passing `recipient_owner` instead pays
+     * a PDA owned by that program, such as an in-game escrow.
      * 
      * @param ctx - Context containing disbursement accounts
-     * @param amount - Amount to disburse in lamports
+     * @param amount - Base amount in lamports, scaled by the epoch's reward multiplier
+     * @param proof - Merkle proof of the recipient against the epoch allowlist
+     * @param memo - Optional short memo surfaced in the disbursement event
+     * @param recipient_owner - Expected owner program when paying a PDA (None = wallet)
+     * @param fee_exempt_proof - Proof of the recipient against the fee-exempt root, if exempt
      */
-    pub fn disburse_sol(ctx: Context<DisburseSol>, amount: u64) -> Result<()> {
-        require!(amount > 0, RewardVaultError::InvalidAmount);
-        require!(ctx.accounts.reward_vault.pay_sol, RewardVaultError::WrongPayoutMode);
+    pub fn disburse_sol(
+        ctx: Context<DisburseSol>,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+        memo: Option<String>,
+        recipient_owner: Option<Pubkey>,
+        fee_exempt_proof: Option<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        check_recipient_owner(&ctx.accounts.recipient, recipient_owner)?;
 
-        let distributor = &ctx.accounts.distributor_signer;
-        
-        // Verify distributor authorization
-        require_keys_eq!(distributor.key(), ctx.accounts.reward_vault.distributor, RewardVaultError::UnauthorizedDistributor);
-        require!(distributor.is_signer, RewardVaultError::MissingDistributorSignature);
+        // Run the checks shared with validate_disbursement
+        let recipient = ctx.accounts.recipient.key();
+        let now = Clock::get()?.unix_timestamp;
+        let amount = check_disbursement(
+            &DisbursementChecks {
+                reward_vault: &ctx.accounts.reward_vault,
+                epoch: ctx.accounts.epoch.as_ref(),
+                distributor_signer: &ctx.accounts.distributor_signer,
+                session_key: ctx.accounts.session_key.as_ref(),
+                admin_signer: ctx.accounts.admin_signer.as_ref(),
+                blocklist: ctx.accounts.blocklist.as_ref(),
+                recipient_stats: Some(&ctx.accounts.recipient_stats),
+            },
+            true,
+            recipient,
+            amount,
+            &proof,
+            memo.as_deref(),
+            now,
+        )?;
+
+        // Partners on the fee-exempt list are paid the full amount
+        let fee = protocol_fee(&ctx.accounts.reward_vault, recipient, amount, fee_exempt_proof.as_deref())?;
+        let payout = amount.checked_sub(fee).ok_or(RewardVaultError::ArithmeticOverflow)?;
+
+        log_compute_units(&ctx.accounts.reward_vault, "payout start");
+        pay_out_sol(&ctx, amount, fee)?;
+        log_compute_units(&ctx.accounts.reward_vault, "payout end");
+        record_epoch_disbursement(ctx.accounts.epoch.as_mut(), amount)?;
 
-        pay_out_sol(&ctx, amount)
+        // Accumulate lifetime earnings for the recipient
+        record_recipient_payout(
+            &mut ctx.accounts.recipient_stats,
+            ctx.accounts.reward_vault.key(),
+            recipient,
+            payout,
+            ctx.bumps.recipient_stats,
+        )?;
+
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &[(recipient, payout)],
+            fee,
+            now,
+        )?;
+        ctx.accounts.reward_vault.last_disburse_ts = now;
+
+        if ctx.accounts.reward_vault.emit_events {
+            emit!(RewardDisbursed {
+                vault: ctx.accounts.reward_vault.key(),
+                recipient,
+                amount: payout,
+                memo,
+                vault_balance_after: vault_balance_after(&ctx.accounts.reward_vault, None)?,
+            });
+        }
+
+        Ok(())
     }
 
     /**
@@ -163,331 +477,8611 @@ pub mod reward_vault {
      * for SPL token payments.
      * 
      * @param ctx - Context containing disbursement accounts
-     * @param amount - Amount to disburse in token units
+     * @param amount - Base amount in token units, scaled by the epoch's reward multiplier
+     * @param proof - Merkle proof of the recipient against the epoch allowlist
+     * @param memo - Optional short memo surfaced in the disbursement event
+     * @param expected_recipient - Wallet the recipient token account must belong to, if given
+     * @param fee_exempt_proof - Proof of the recipient against the fee-exempt root, if exempt
      */
-    pub fn disburse_spl(ctx: Context<DisburseSpl>, amount: u64) -> Result<()> {
-        require!(amount > 0, RewardVaultError::InvalidAmount);
-        require!(!ctx.accounts.reward_vault.pay_sol, RewardVaultError::WrongPayoutMode);
+    pub fn disburse_spl(
+        ctx: Context<DisburseSpl>,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+        memo: Option<String>,
+        expected_recipient: Option<Pubkey>,
+        fee_exempt_proof: Option<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        // Catch payouts to the wrong token account, such as the vault's own ATA
+        if let Some(expected_recipient) = expected_recipient {
+            require_keys_eq!(
+                ctx.accounts.recipient_token_account.owner,
+                expected_recipient,
+                RewardVaultError::RecipientMismatch
+            );
+        }
 
-        let distributor = &ctx.accounts.distributor_signer;
-        
-        // Verify distributor authorization
-        require_keys_eq!(distributor.key(), ctx.accounts.reward_vault.distributor, RewardVaultError::UnauthorizedDistributor);
-        require!(distributor.is_signer, RewardVaultError::MissingDistributorSignature);
+        // Run the checks shared with validate_disbursement, keyed on the token account owner
+        let recipient = ctx.accounts.recipient_token_account.owner;
+        let now = Clock::get()?.unix_timestamp;
+        let amount = check_disbursement(
+            &DisbursementChecks {
+                reward_vault: &ctx.accounts.reward_vault,
+                epoch: ctx.accounts.epoch.as_ref(),
+                distributor_signer: &ctx.accounts.distributor_signer,
+                session_key: ctx.accounts.session_key.as_ref(),
+                admin_signer: ctx.accounts.admin_signer.as_ref(),
+                blocklist: ctx.accounts.blocklist.as_ref(),
+                recipient_stats: Some(&ctx.accounts.recipient_stats),
+            },
+            false,
+            recipient,
+            amount,
+            &proof,
+            memo.as_deref(),
+            now,
+        )?;
 
-        pay_out_spl(&ctx, amount)
-    }
-}
+        // Partners on the fee-exempt list are paid the full amount
+        let fee = protocol_fee(&ctx.accounts.reward_vault, recipient, amount, fee_exempt_proof.as_deref())?;
+        let payout = amount.checked_sub(fee).ok_or(RewardVaultError::ArithmeticOverflow)?;
 
-// ============================================================================
-// HELPER FUNCTIONS
-// ============================================================================
+        log_compute_units(&ctx.accounts.reward_vault, "payout start");
+        pay_out_spl(&ctx, amount, fee)?;
+        log_compute_units(&ctx.accounts.reward_vault, "payout end");
+        record_epoch_disbursement(ctx.accounts.epoch.as_mut(), amount)?;
 
-/**
- * Fund vault with SOL
- * 
- * Transfers SOL from funder to vault using system program instruction.
- * This is a low-level function that handles the actual SOL transfer.
- */
-fn fund_sol(ctx: &Context<FundVault>, amount: u64) -> Result<()> {
-    let funder = ctx.accounts.funder.to_account_info();
-    let reward_vault_info = ctx.accounts.reward_vault.to_account_info();
-    let system_program = ctx.accounts.system_program.to_account_info();
+        // Accumulate lifetime earnings for the token account owner
+        record_recipient_payout(
+            &mut ctx.accounts.recipient_stats,
+            ctx.accounts.reward_vault.key(),
+            recipient,
+            payout,
+            ctx.bumps.recipient_stats,
+        )?;
 
-    // Create and invoke system transfer instruction
-    let transfer_ix = system_instruction::transfer(funder.key, reward_vault_info.key, amount);
-    invoke(&transfer_ix, &[funder, reward_vault_info, system_program])?;
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &[(recipient, payout)],
+            fee,
+            now,
+        )?;
+        ctx.accounts.reward_vault.last_disburse_ts = now;
 
-    Ok(())
-}
+        if ctx.accounts.reward_vault.emit_events {
+            emit!(RewardDisbursed {
+                vault: ctx.accounts.reward_vault.key(),
+                recipient,
+                amount: payout,
+                memo,
+                vault_balance_after: vault_balance_after(&ctx.accounts.reward_vault, Some(ctx.accounts.epoch_vault_token_account.as_ref().unwrap_or(&ctx.accounts.vault_token_account).as_ref()))?,
+            });
+        }
 
-/**
- * Fund vault with SPL tokens
- * 
- * Transfers SPL tokens from funder to vault using token program.
- * Validates mint addresses and handles token account transfers.
- */
-fn fund_spl(ctx: &Context<FundVault>, amount: u64) -> Result<()> {
-    let reward_vault = &ctx.accounts.reward_vault;
-    let reward_mint = reward_vault
-        .reward_mint
-        .ok_or(RewardVaultError::RewardMintRequired)?;
+        Ok(())
+    }
 
-    let vault_token = ctx
-        .accounts
-        .vault_token_account
-        .as_ref()
-        .ok_or(RewardVaultError::VaultTokenRequired)?;
+    /**
+     * Disburse SOL rewards to a batch of recipients
+     * 
+     * Distributes SOL from the vault to several recipients in one transaction.
+     * Recipients are passed as writable `remaining_accounts` in `(recipient,
+     * recipient stats PDA)` pairs, matched by position with `amounts`. Every
+     * recipient goes through the epoch, allowlist, blocklist, and cooldown
+     * checks of `disburse_sol`, and the whole batch is validated before any
+     * lamports move. The protocol fee is withheld from every recipient;
+     * batches carry no fee-exemption proofs.
+     * 
+     * @param ctx - Context containing disbursement accounts and recipients
+     * @param amounts - Base amount in lamports for each recipient, scaled by the epoch's reward multiplier
+     * @param proofs - Allowlist proof for each recipient, in order (empty = epoch has no allowlist)
+     */
+    pub fn disburse_sol_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DisburseSolBatch<'info>>,
+        amounts: Vec<u64>,
+        proofs: Vec<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        // Run the batch-wide checks shared with disburse_split
+        let now = Clock::get()?.unix_timestamp;
+        let checks = DisbursementChecks {
+            reward_vault: &ctx.accounts.reward_vault,
+            epoch: ctx.accounts.epoch.as_ref(),
+            distributor_signer: &ctx.accounts.distributor_signer,
+            session_key: ctx.accounts.session_key.as_ref(),
+            admin_signer: ctx.accounts.admin_signer.as_ref(),
+            blocklist: ctx.accounts.blocklist.as_ref(),
+            recipient_stats: None,
+        };
+        check_batch_disbursement(&checks, true, ctx.remaining_accounts, amounts.len(), &proofs, now)?;
+        let amounts = scale_batch(&checks, &amounts)?;
 
-    let funder_token = ctx
-        .accounts
-        .funder_token_account
-        .as_ref()
-        .ok_or(RewardVaultError::FunderTokenRequired)?;
+        log_compute_units(&ctx.accounts.reward_vault, "batch payout start");
+        let (payouts, fees) = pay_out_sol_batch(&ctx, &checks, &amounts, &proofs, now)?;
+        log_compute_units(&ctx.accounts.reward_vault, "batch payout end");
+        let disbursed = batch_disbursed(&payouts, fees)?;
+        record_epoch_disbursement(ctx.accounts.epoch.as_mut(), disbursed)?;
 
-    // Validate mint addresses match
-    require_keys_eq!(vault_token.mint, reward_mint, RewardVaultError::MintMismatch);
-    require_keys_eq!(funder_token.mint, reward_mint, RewardVaultError::MintMismatch);
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &payouts,
+            fees,
+            now,
+        )?;
+        ctx.accounts.reward_vault.last_disburse_ts = now;
 
-    let token_program = ctx.accounts.token_program.to_account_info();
+        if ctx.accounts.reward_vault.emit_events {
+            let vault_balance_after = vault_balance_after(&ctx.accounts.reward_vault, None)?;
+            for (recipient, amount) in payouts {
+                emit!(RewardDisbursed {
+                    vault: ctx.accounts.reward_vault.key(),
+                    recipient,
+                    amount,
+                    memo: None,
+                    vault_balance_after,
+                });
+            }
+        }
 
-    // Create token transfer instruction
-    let cpi_accounts = Transfer {
-        from: funder_token.to_account_info(),
-        to: vault_token.to_account_info(),
-        authority: ctx.accounts.funder.to_account_info(),
-    };
+        Ok(())
+    }
 
-    // Execute token transfer
-    token::transfer(CpiContext::new(token_program, cpi_accounts), amount)?;
+    /**
+     * Initialize a stake snapshot for an epoch
+     * 
+     * Creates the snapshot account that aggregates recorded stake weights
+     * for proportional reward distribution.
+     * 
+     * @param ctx - Context containing admin, vault, epoch, and snapshot accounts
+     * @param rounding - How proportional shares are rounded
+     */
+    pub fn init_stake_snapshot(ctx: Context<InitStakeSnapshot>, rounding: RoundingMode) -> Result<()> {
+        let stake_snapshot = &mut ctx.accounts.stake_snapshot;
 
-    Ok(())
-}
+        stake_snapshot.epoch = ctx.accounts.epoch.key();
+        stake_snapshot.total_weight = 0;
+        stake_snapshot.rounding = rounding;
+        stake_snapshot.total_claimed = 0;
+        stake_snapshot.bump = ctx.bumps.stake_snapshot;
 
-/**
- * Pay out SOL from vault
- * 
- * Directly transfers SOL from vault to recipient by modifying lamports.
- * This is a low-level operation that bypasses the system program.
- */
-fn pay_out_sol(ctx: &Context<DisburseSol>, amount: u64) -> Result<()> {
-    let reward_vault_info = ctx.accounts.reward_vault.to_account_info();
-    let recipient_info = ctx.accounts.recipient.to_account_info();
+        Ok(())
+    }
 
-    // Check vault has sufficient balance
-    let balance = reward_vault_info.lamports();
-    require!(balance >= amount, RewardVaultError::InsufficientVaultBalance);
+    /**
+     * Record a recipient's stake weight
+     * 
+     * Sets the weight for a recipient in the epoch snapshot, adjusting the
+     * snapshot total by the difference. Weights can only be recorded until
+     * the epoch ends, after which claims are computed against them.
+     * 
+     * @param ctx - Context containing admin, snapshot, and weight accounts
+     * @param recipient - Recipient the weight is recorded for
+     * @param weight - Stake weight for the recipient
+     */
+    pub fn record_weight(ctx: Context<RecordWeight>, recipient: Pubkey, weight: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < ctx.accounts.epoch.end_ts, RewardVaultError::EpochAlreadyEnded);
 
-    // Transfer lamports directly
-    **reward_vault_info.try_borrow_mut_lamports()? -= amount;
-    **recipient_info.try_borrow_mut_lamports()? += amount;
+        let stake_snapshot = &mut ctx.accounts.stake_snapshot;
+        let recipient_weight = &mut ctx.accounts.weight;
 
-    Ok(())
-}
+        // Replace any previously recorded weight in the snapshot total
+        stake_snapshot.total_weight = stake_snapshot
+            .total_weight
+            .checked_sub(u128::from(recipient_weight.weight))
+            .and_then(|total| total.checked_add(u128::from(weight)))
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
 
-/**
- * Pay out SPL tokens from vault
- * 
- * Transfers SPL tokens from vault to recipient using token program.
- * Uses program-derived address for vault authority.
- */
-fn pay_out_spl(ctx: &Context<DisburseSpl>, amount: u64) -> Result<()> {
-    let reward_vault = &ctx.accounts.reward_vault;
-    let reward_mint = reward_vault
-        .reward_mint
-        .ok_or(RewardVaultError::RewardMintRequired)?;
+        recipient_weight.epoch = ctx.accounts.epoch.key();
+        recipient_weight.recipient = recipient;
+        recipient_weight.weight = weight;
+        recipient_weight.bump = ctx.bumps.weight;
 
-    let vault_token = &ctx.accounts.vault_token_account;
-    let recipient_token = &ctx.accounts.recipient_token_account;
+        Ok(())
+    }
 
-    // Validate mint addresses
-    require_keys_eq!(vault_token.mint, reward_mint, RewardVaultError::MintMismatch);
-    require_keys_eq!(recipient_token.mint, reward_mint, RewardVaultError::MintMismatch);
+    /**
+     * Claim a proportional share of an epoch's rewards
+     * 
+     * Pays the recipient `total_funded * weight / total_weight`, rounded per
+     * the snapshot's rounding mode, less anything already claimed. Once
+     * funding is finalized, the frozen `finalized_amount` is used. Claims
+     * open once the epoch has ended. Rounding up can never pay out more than
+     * `total_funded` in total: the claim that would exceed it is capped.
+     * 
+     * @param ctx - Context containing recipient, vault, snapshot, and weight accounts
+     */
+    pub fn claim_proportional(ctx: Context<ClaimProportional>) -> Result<()> {
+        let epoch = &ctx.accounts.epoch;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= epoch.end_ts, RewardVaultError::EpochNotEnded);
+        check_not_blocked(&ctx.accounts.reward_vault, ctx.accounts.blocklist.as_ref(), ctx.accounts.recipient.key())?;
 
-    let token_program = ctx.accounts.token_program.to_account_info();
+        let total_weight = ctx.accounts.stake_snapshot.total_weight;
+        require!(total_weight > 0, RewardVaultError::ZeroTotalWeight);
 
-    // Create program-derived address for vault authority
-    let seeds: [&[u8]; 3] = [RewardVault::SEED, reward_vault.admin.as_ref(), &[reward_vault.bump]];
-    let signer = &[&seeds[..]];
+        let recipient_weight = &ctx.accounts.weight;
 
-    // Create token transfer instruction with program authority
-    let cpi_accounts = Transfer {
-        from: vault_token.to_account_info(),
-        to: recipient_token.to_account_info(),
-        authority: reward_vault.to_account_info(),
-    };
+        // Compute the share owed so far with overflow protection
+        let stake_snapshot = &ctx.accounts.stake_snapshot;
+        let share = epoch
+            .distributable_funding()
+            .checked_mul(u128::from(recipient_weight.weight))
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        let entitled = stake_snapshot.rounding.divide(share, total_weight);
+        let entitled = u64::try_from(entitled).map_err(|_| RewardVaultError::ArithmeticOverflow)?;
 
-    // Execute token transfer with program signature
-    token::transfer(CpiContext::new_with_signer(token_program, cpi_accounts, signer), amount)?;
+        // Cap the claim so rounding never pays out more than was funded
+        let unclaimed = epoch.distributable_funding().saturating_sub(stake_snapshot.total_claimed);
+        let amount = entitled.saturating_sub(recipient_weight.claimed);
+        let amount = u64::try_from(u128::from(amount).min(unclaimed)).map_err(|_| RewardVaultError::ArithmeticOverflow)?;
+        require!(amount > 0, RewardVaultError::NothingToClaim);
 
-    Ok(())
-}
+        // Route payout based on vault configuration
+        if ctx.accounts.reward_vault.pay_sol {
+            pay_out_proportional_sol(&ctx, amount)?;
+        } else {
+            pay_out_proportional_spl(&ctx, amount)?;
+        }
 
-// ============================================================================
-// ACCOUNT STRUCTURES
-// ============================================================================
+        let recipient_weight = &mut ctx.accounts.weight;
+        recipient_weight.claimed = recipient_weight
+            .claimed
+            .checked_add(amount)
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
 
-/**
- * Initialize Vault Context
- * 
- * Accounts required for vault initialization including admin signer,
- * vault account creation, and system program.
- */
-#[derive(Accounts)]
-pub struct InitVault<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
-    #[account(
-        init,
-        payer = admin,
-        space = RewardVault::LEN,
-        seeds = [RewardVault::SEED, admin.key().as_ref()],
-        bump
-    )]
-    pub reward_vault: Account<'info, RewardVault>,
-    pub system_program: Program<'info, System>,
-}
+        let stake_snapshot = &mut ctx.accounts.stake_snapshot;
+        stake_snapshot.total_claimed = stake_snapshot
+            .total_claimed
+            .checked_add(u128::from(amount))
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
 
-/**
+        let recipient = ctx.accounts.recipient.key();
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &[(recipient, amount)],
+            0,
+            now,
+        )?;
+
+        record_epoch_disbursement(Some(&mut ctx.accounts.epoch), amount)
+    }
+
+    /**
+     * Validate a disbursement without moving funds
+     * 
+     * Runs the same checks as disburse_sol/disburse_spl through the helpers
+     * those instructions use, but performs no transfer and changes no state.
+     * Intended to be called in a simulated transaction for a clean
+     * pass/fail. Omit `recipient_stats` for a recipient never paid before.
+     * 
+     * @param ctx - Context containing vault, distributor, and balance accounts
+     * @param amount - Base amount to validate in lamports or token units
+     * @param pay_sol - Whether to validate disburse_sol (true) or disburse_spl (false)
+     * @param recipient - Wallet that would be paid
+     * @param proof - Merkle proof of the recipient against the epoch allowlist
+     * @param memo - Memo the disbursement would carry
+     */
+    pub fn validate_disbursement(
+        ctx: Context<ValidateDisbursement>,
+        amount: u64,
+        pay_sol: bool,
+        recipient: Pubkey,
+        proof: Vec<[u8; 32]>,
+        memo: Option<String>,
+    ) -> Result<()> {
+        let reward_vault = &ctx.accounts.reward_vault;
+        let amount = check_disbursement(
+            &DisbursementChecks {
+                reward_vault,
+                epoch: ctx.accounts.epoch.as_ref(),
+                distributor_signer: &ctx.accounts.distributor_signer,
+                session_key: ctx.accounts.session_key.as_ref(),
+                admin_signer: ctx.accounts.admin_signer.as_ref(),
+                blocklist: ctx.accounts.blocklist.as_ref(),
+                recipient_stats: ctx.accounts.recipient_stats.as_ref(),
+            },
+            pay_sol,
+            recipient,
+            amount,
+            &proof,
+            memo.as_deref(),
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        // Check the balance backing the vault's payout mode
+        if reward_vault.pay_sol {
+            check_payout_balance(reward_vault, reward_vault.to_account_info().lamports(), amount)?;
+        } else {
+            let vault_token = payout_token_account(
+                reward_vault,
+                ctx.accounts.epoch.as_ref(),
+                ctx.accounts.vault_token_account.as_ref().ok_or(RewardVaultError::VaultTokenRequired)?,
+                ctx.accounts.epoch_vault_token_account.as_ref(),
+            )?;
+            require!(!vault_token.is_frozen(), RewardVaultError::VaultTokenFrozen);
+            check_payout_balance(reward_vault, vault_token.amount, amount)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Sweep an epoch's unspent funds
+     * 
+     * Sends whatever is left of an epoch's payout budget to the vault
+     * treasury. Only allowed for an ended epoch once its end plus its
+     * claim grace period has elapsed, and moves the epoch to Swept. Epochs
+     * with sub-epochs must pass every one of them in `remaining_accounts`
+     * so their payouts are counted first.
+     * 
+     * @param ctx - Context containing admin, vault, epoch, treasury, and token accounts
+     */
+    pub fn sweep_epoch<'info>(ctx: Context<'_, '_, 'info, 'info, SweepEpoch<'info>>) -> Result<()> {
+        let epoch = &ctx.accounts.epoch;
+        let now = Clock::get()?.unix_timestamp;
+        let status = epoch.effective_status(now);
+        require!(status != EpochStatus::Swept, RewardVaultError::EpochAlreadySwept);
+        require!(status == EpochStatus::Ended, RewardVaultError::InvalidEpochStatus);
+        require!(!epoch.rolled_over, RewardVaultError::EpochAlreadyRolledOver);
+
+        // Funds stay claimable until the grace window has elapsed
+        let sweepable_at = epoch
+            .end_ts
+            .checked_add(epoch.claim_grace_seconds)
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        require!(now >= sweepable_at, RewardVaultError::ClaimWindowOpen);
+        require!(
+            epoch.claim_deadline == 0 || now > epoch.claim_deadline,
+            RewardVaultError::ClaimWindowOpen
+        );
+
+        settle_sub_epochs(&mut ctx.accounts.epoch, ctx.remaining_accounts)?;
+        let epoch = &ctx.accounts.epoch;
+        let remaining = epoch.payout_budget()?.saturating_sub(epoch.total_disbursed);
+        let amount = u64::try_from(remaining).map_err(|_| RewardVaultError::ArithmeticOverflow)?;
+
+        if amount > 0 {
+            if ctx.accounts.reward_vault.pay_sol {
+                sweep_sol(&ctx, amount)?;
+            } else {
+                sweep_spl(&ctx, amount)?;
+            }
+        }
+
+        let epoch = &mut ctx.accounts.epoch;
+        epoch.status = EpochStatus::Swept;
+
+        emit!(EpochSwept {
+            vault: ctx.accounts.reward_vault.key(),
+            epoch_index: epoch.index,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * Roll an epoch's unspent funds into another epoch
+     * 
+     * Moves the unspent remainder of an ended epoch into the budget of a
+     * later epoch of the same vault instead of sweeping it. Each epoch can
+     * only be rolled over once, and pays out nothing further afterwards. Epochs with sub-epochs must pass every one
+     * of them in `remaining_accounts`.
+     * 
+     * @param ctx - Context containing admin, vault, and both epoch accounts
+     * @param from_index - Index of the epoch giving up its remainder
+     * @param to_index - Index of the epoch receiving the remainder
+     */
+    pub fn rollover_epoch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RolloverEpoch<'info>>,
+        from_index: u64,
+        to_index: u64,
+    ) -> Result<()> {
+        require!(from_index != to_index, RewardVaultError::InvalidRolloverTarget);
+
+        let from_epoch = &mut ctx.accounts.from_epoch;
+        require!(!from_epoch.rolled_over, RewardVaultError::EpochAlreadyRolledOver);
+        let now = Clock::get()?.unix_timestamp;
+        let status = from_epoch.effective_status(now);
+        require!(status != EpochStatus::Swept, RewardVaultError::EpochAlreadySwept);
+        require!(status == EpochStatus::Ended, RewardVaultError::InvalidEpochStatus);
+        require!(now >= from_epoch.end_ts, RewardVaultError::EpochNotEnded);
+
+        settle_sub_epochs(from_epoch, ctx.remaining_accounts)?;
+        let remaining = from_epoch.payout_budget()?.saturating_sub(from_epoch.total_disbursed);
+
+        // Safely add the remainder to the target epoch's budget
+        let to_epoch = &mut ctx.accounts.to_epoch;
+        require!(!to_epoch.funding_locked, RewardVaultError::FundingLocked);
+        to_epoch.total_funded = to_epoch
+            .total_funded
+            .checked_add(remaining)
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        require!(
+            to_epoch.hard_cap == 0 || to_epoch.total_funded <= to_epoch.hard_cap,
+            RewardVaultError::FundingCapExceeded
+        );
+
+        from_epoch.rolled_over = true;
+
+        Ok(())
+    }
+
+    /**
+     * Redeem a signed reward voucher
+     * 
+     * Pays out a voucher signed off-chain by the vault distributor, the
+     * epoch's distributor override, or a live session key of either. The
+     * transaction must carry an ed25519 verification instruction directly
+     * before this one covering `(vault, recipient, amount, expiry, nonce)`,
+     * followed by the epoch key when the voucher is charged to an epoch.
+     * A receipt PDA per nonce prevents the voucher being redeemed twice.
+     * Vouchers are subject to the same pause, co-signature, amount,
+     * blocklist, epoch, and balance checks as distributor payouts, and pay
+     * the protocol fee the same way.
+     * 
+     * @param ctx - Context containing recipient, vault, receipt, epoch, signer, treasury, and token accounts
+     * @param amount - Voucher amount in lamports or token units
+     * @param expiry - Unix timestamp after which the voucher is invalid
+     * @param nonce - Unique voucher nonce
+     * @param signature - Distributor's ed25519 signature over the voucher
+     * @param fee_exempt_proof - Proof of the recipient against the fee-exempt root, if exempt
+     */
+    pub fn redeem_voucher(
+        ctx: Context<RedeemVoucher>,
+        amount: u64,
+        expiry: i64,
+        nonce: u64,
+        signature: [u8; 64],
+        fee_exempt_proof: Option<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        require!(amount > 0, RewardVaultError::InvalidAmount);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now <= expiry, RewardVaultError::VoucherExpired);
+
+        let reward_vault = &ctx.accounts.reward_vault;
+        let epoch = ctx.accounts.epoch.as_ref();
+        let voucher_signer = authorize_voucher(
+            reward_vault,
+            epoch,
+            ctx.accounts.session_key.as_ref(),
+            ctx.accounts.admin_signer.as_ref(),
+        )?;
+        check_disbursement_amount(reward_vault, amount)?;
+
+        let recipient = ctx.accounts.recipient.key();
+        check_not_blocked(reward_vault, ctx.accounts.blocklist.as_ref(), recipient)?;
+
+        check_epoch_active(epoch)?;
+        check_epoch_funded(reward_vault, epoch)?;
+
+        // Rebuild the signed voucher message and check the distributor signed it
+        let mut message = [
+            reward_vault.key().as_ref(),
+            recipient.as_ref(),
+            &amount.to_le_bytes(),
+            &expiry.to_le_bytes(),
+            &nonce.to_le_bytes(),
+        ]
+        .concat();
+        if let Some(epoch) = epoch {
+            message.extend_from_slice(epoch.key().as_ref());
+        }
+        verify_ed25519_signature(
+            &ctx.accounts.instructions_sysvar,
+            &voucher_signer,
+            &message,
+            &signature,
+        )?;
+
+        // Partners on the fee-exempt list are paid the full amount
+        let fee = protocol_fee(reward_vault, recipient, amount, fee_exempt_proof.as_deref())?;
+        let payout = amount.checked_sub(fee).ok_or(RewardVaultError::ArithmeticOverflow)?;
+
+        // Route payout based on vault configuration
+        if reward_vault.pay_sol {
+            check_payout_balance(reward_vault, reward_vault.to_account_info().lamports(), amount)?;
+            transfer_vault_lamports(reward_vault, &ctx.accounts.recipient.to_account_info(), payout)?;
+            pay_fee_sol(reward_vault, ctx.accounts.treasury.as_ref(), fee)?;
+        } else {
+            let vault_token = ctx
+                .accounts
+                .vault_token_account
+                .as_ref()
+                .ok_or(RewardVaultError::VaultTokenRequired)?;
+            check_payout_balance(reward_vault, vault_token.amount, amount)?;
+            pay_out_tokens(
+                reward_vault,
+                Some(vault_token),
+                ctx.accounts.recipient_token_account.as_ref(),
+                recipient,
+                &ctx.accounts.token_program,
+                payout,
+            )?;
+            pay_fee_spl(
+                reward_vault,
+                vault_token,
+                ctx.accounts.treasury_token_account.as_ref(),
+                &ctx.accounts.token_program,
+                fee,
+            )?;
+        }
+
+        record_epoch_disbursement(ctx.accounts.epoch.as_mut(), amount)?;
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &[(recipient, payout)],
+            fee,
+            now,
+        )?;
+
+        let voucher_receipt = &mut ctx.accounts.voucher_receipt;
+        voucher_receipt.vault = ctx.accounts.reward_vault.key();
+        voucher_receipt.recipient = recipient;
+        voucher_receipt.amount = amount;
+        voucher_receipt.nonce = nonce;
+        voucher_receipt.bump = ctx.bumps.voucher_receipt;
+
+        Ok(())
+    }
+
+    /**
+     * Set the minimum disbursement amount
+     * 
+     * Updates the smallest payout the vault will make, to keep dust
+     * disbursements from wasting compute and cluttering histories.
+     * 
+     * @param ctx - Context containing admin or operator and vault accounts
+     * @param min_disbursement - Smallest allowed disbursement (0 = any non-zero amount)
+     */
+    pub fn set_min_disbursement(ctx: Context<ConfigureVault>, min_disbursement: u64) -> Result<()> {
+        ctx.accounts.reward_vault.min_disbursement = min_disbursement;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Set the funder allowlist
+     * 
+     * Restricts who may fund the vault to members of a Merkle tree of
+     * funder keys. A zeroed root reopens funding to anyone.
+     * 
+     * @param ctx - Context containing admin or operator and vault accounts
+     * @param funder_allowlist_root - Merkle root of allowed funders (zeroed = open)
+     */
+    pub fn set_funder_allowlist(ctx: Context<ConfigureVault>, funder_allowlist_root: [u8; 32]) -> Result<()> {
+        ctx.accounts.reward_vault.funder_allowlist_root = funder_allowlist_root;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Get an epoch summary
+     * 
+     * Read-only view returning an epoch's funding totals, computed remaining
+     * balance, window, and whether it is currently active. The summary is
+     * delivered to clients as the instruction's return data.
+     * 
+     * @param ctx - Context containing the epoch account
+     */
+    pub fn get_epoch_summary(ctx: Context<GetEpochSummary>) -> Result<EpochSummary> {
+        let epoch = &ctx.accounts.epoch;
+        let now = Clock::get()?.unix_timestamp;
+
+        Ok(EpochSummary {
+            total_funded: epoch.total_funded,
+            total_disbursed: epoch.total_disbursed,
+            remaining: epoch.payout_budget()?.saturating_sub(epoch.total_disbursed),
+            start_ts: epoch.start_ts,
+            end_ts: epoch.end_ts,
+            active: epoch.effective_status(now) == EpochStatus::Active,
+        })
+    }
+
+    /**
+     * Get an epoch's annualized reward rate
+     * 
+     * Read-only view returning `total_funded * APR_SCALE / staked_total`,
+     * annualized over the epoch's duration. The rate is delivered to
+     * clients as the instruction's return data.
+     * 
+     * @param ctx - Context containing the epoch account
+     * @param staked_total - Total stake the epoch's rewards are spread over
+     */
+    pub fn get_epoch_rate(ctx: Context<GetEpochSummary>, staked_total: u128) -> Result<u64> {
+        ctx.accounts.epoch.annualized_rate(staked_total)
+    }
+
+    /**
+     * Set an epoch's funding cap
+     * 
+     * Records the funding target for an epoch. Reaching it emits a
+     * FundingCapReached event so operators can tell funders to stop.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     * @param funding_cap - Funding target for the epoch (0 = no cap)
+     */
+    pub fn set_funding_cap(ctx: Context<UpdateEpoch>, funding_cap: u128) -> Result<()> {
+        let epoch = &mut ctx.accounts.epoch;
+        epoch.funding_cap = funding_cap;
+        epoch.funding_cap_reached = false;
+
+        Ok(())
+    }
+
+    /**
+     * Set an epoch's hard funding cap
+     * 
+     * Unlike the funding cap, which only signals that a target was met, the
+     * hard cap is enforced: no funding may take the epoch's total past it,
+     * however many funders contribute. Each funding checks the cap against
+     * the epoch account it writes, so racing funders are serialized and the
+     * one that would overshoot fails.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     * @param hard_cap - Maximum total funding for the epoch (0 = unlimited)
+     */
+    pub fn set_hard_cap(ctx: Context<UpdateEpoch>, hard_cap: u128) -> Result<()> {
+        let epoch = &mut ctx.accounts.epoch;
+        require!(hard_cap == 0 || hard_cap >= epoch.total_funded, RewardVaultError::FundingCapExceeded);
+        epoch.hard_cap = hard_cap;
+
+        Ok(())
+    }
+
+    /**
+     * Set the distributor cooldown
+     * 
+     * Sets the minimum number of seconds between disbursements, slowing a
+     * potentially compromised distributor. Zero disables the cooldown.
+     * 
+     * @param ctx - Context containing admin or operator and vault accounts
+     * @param disburse_cooldown - Minimum seconds between disbursements
+     */
+    pub fn set_disburse_cooldown(ctx: Context<ConfigureVault>, disburse_cooldown: i64) -> Result<()> {
+        require!(disburse_cooldown >= 0, RewardVaultError::InvalidCooldown);
+        ctx.accounts.reward_vault.disburse_cooldown = disburse_cooldown;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Set an epoch's claim root
+     * 
+     * Publishes the Merkle root of `(epoch_index, recipient, amount)`
+     * allocations that recipients can claim from the epoch.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     * @param claim_root - Merkle root of claim allocations (zeroed = claims disabled)
+     */
+    pub fn set_claim_root(ctx: Context<UpdateEpoch>, claim_root: [u8; 32]) -> Result<()> {
+        ctx.accounts.epoch.claim_root = claim_root;
+
+        Ok(())
+    }
+
+    /**
+     * Claim a Merkle-allocated reward
+     * 
+     * Pays part or all of the amount allocated to the signing recipient in
+     * the epoch's claim tree. The claim PDA per epoch and recipient tracks
+     * how much has been claimed, so repeated partial claims can never exceed
+     * the allocation. The payout can go to a different wallet, such as a
+     * cold wallet, while the leaf and claim PDA stay bound to the signer.
+     * 
+     * @param ctx - Context containing recipient, vault, epoch, claim, and token accounts
+     * @param amount - Allocated amount in lamports or token units
+     * @param claim_amount - Portion of the allocation to pay now
+     * @param proof - Merkle proof of `(epoch_index, recipient, amount)` against the claim root
+     * @param destination - Wallet to pay instead of the signer (None = signer)
+     */
+    pub fn claim(
+        ctx: Context<ClaimReward>,
+        amount: u64,
+        claim_amount: u64,
+        proof: Vec<[u8; 32]>,
+        destination: Option<Pubkey>,
+    ) -> Result<()> {
+        require!(amount > 0, RewardVaultError::InvalidAmount);
+
+        // Verify the allocation against the epoch claim tree
+        let recipient = ctx.accounts.recipient.key();
+        check_claim(&ctx.accounts.epoch, recipient, amount, &proof)?;
+        check_terms_acknowledged(&ctx.accounts.epoch, recipient, ctx.accounts.acknowledgment.as_ref())?;
+        let payee = destination.unwrap_or(recipient);
+        check_not_blocked(&ctx.accounts.reward_vault, ctx.accounts.blocklist.as_ref(), recipient)?;
+        check_not_blocked(&ctx.accounts.reward_vault, ctx.accounts.blocklist.as_ref(), payee)?;
+
+        // Only the unclaimed part of the allocation can be paid
+        let claimed = check_claim_amount(&ctx.accounts.claim, amount, claim_amount)?;
+
+        // Route payout based on vault configuration
+        let reward_vault = &ctx.accounts.reward_vault;
+        if reward_vault.pay_sol {
+            let payee_info = match destination {
+                Some(destination) => {
+                    let destination_info = ctx
+                        .accounts
+                        .destination
+                        .as_ref()
+                        .ok_or(RewardVaultError::DestinationRequired)?;
+                    require_keys_eq!(destination_info.key(), destination, RewardVaultError::RecipientMismatch);
+                    check_recipient_owner(destination_info, None)?;
+                    destination_info.to_account_info()
+                }
+                None => ctx.accounts.recipient.to_account_info(),
+            };
+            transfer_vault_lamports(reward_vault, &payee_info, claim_amount)?;
+        } else {
+            pay_out_tokens(
+                reward_vault,
+                ctx.accounts.vault_token_account.as_ref(),
+                ctx.accounts.recipient_token_account.as_ref(),
+                payee,
+                &ctx.accounts.token_program,
+                claim_amount,
+            )?;
+        }
+
+        record_epoch_disbursement(Some(&mut ctx.accounts.epoch), claim_amount)?;
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &[(payee, claim_amount)],
+            0,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        let claim = &mut ctx.accounts.claim;
+        claim.epoch = ctx.accounts.epoch.key();
+        claim.recipient = recipient;
+        claim.amount = amount;
+        claim.claimed = claimed;
+        claim.bump = ctx.bumps.claim;
+
+        Ok(())
+    }
+
+    /**
+     * Check whether a recipient has claimed an epoch
+     * 
+     * Read-only view returning whether the recipient has claimed their whole
+     * allocation for the epoch. A partially claimed allocation, or a claim
+     * account that was never created, reads as false rather than an error.
+     * Covers the epoch's own claim tree; sub-epoch claims have a claim
+     * account per shard.
+     * 
+     * @param ctx - Context containing the epoch and derived claim account
+     * @param recipient - Recipient to check
+     */
+    pub fn has_claimed(ctx: Context<HasClaimed>, recipient: Pubkey) -> Result<bool> {
+        let claim = Claim::try_read(&ctx.accounts.claim.to_account_info())?;
+
+        Ok(claim.is_some_and(|claim| claim.recipient == recipient && claim.remaining() == 0))
+    }
+
+    /**
+     * Claim Merkle allocations from several epochs at once
+     * 
+     * Verifies each claim against its epoch's claim root, records a claim
+     * PDA per epoch, and pays the summed total in a single transfer. The
+     * `remaining_accounts` are `(epoch, claim PDA)` pairs in the same order
+     * as `claims`. Each epoch pays whatever is left of its allocation, so
+     * epochs claimed in part are finished here; any fully claimed epoch
+     * fails the whole batch.
+     * 
+     * @param ctx - Context containing recipient, vault, and token accounts
+     * @param claims - Epoch index, allocated amount, and proof for each epoch
+     */
+    pub fn claim_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimMany<'info>>,
+        claims: Vec<EpochClaim>,
+    ) -> Result<()> {
+        let accounts = ctx.remaining_accounts;
+        require!(
+            !claims.is_empty() && accounts.len() == claims.len() * 2,
+            RewardVaultError::BatchLengthMismatch
+        );
+        require!(claims.len() <= RewardVault::MAX_BATCH, RewardVaultError::BatchTooLarge);
+
+        let recipient = ctx.accounts.recipient.key();
+        check_not_blocked(&ctx.accounts.reward_vault, ctx.accounts.blocklist.as_ref(), recipient)?;
+        let mut total: u64 = 0;
+        let mut payouts = Vec::with_capacity(claims.len());
+
+        // Verify and record every claim before moving any funds
+        for (epoch_claim, pair) in claims.iter().zip(accounts.chunks(2)) {
+            require!(epoch_claim.amount > 0, RewardVaultError::InvalidAmount);
+
+            let mut epoch = Account::<Epoch>::try_from(&pair[0])?;
+            require_keys_eq!(epoch.vault, ctx.accounts.reward_vault.key(), RewardVaultError::EpochMismatch);
+            require!(epoch.index == epoch_claim.epoch_index, RewardVaultError::EpochMismatch);
+            check_claim(&epoch, recipient, epoch_claim.amount, &epoch_claim.proof)?;
+            check_terms_acknowledged(&epoch, recipient, ctx.accounts.acknowledgment.as_ref())?;
+
+            let payout = record_batch_claim(&ctx, &pair[1], &epoch, epoch_claim.amount)?;
+
+            record_epoch_disbursement(Some(&mut epoch), payout)?;
+            epoch.exit(&crate::ID)?;
+
+            total = total
+                .checked_add(payout)
+                .ok_or(RewardVaultError::ArithmeticOverflow)?;
+            payouts.push((recipient, payout));
+        }
+
+        // Route the combined payout based on vault configuration
+        let reward_vault = &ctx.accounts.reward_vault;
+        if reward_vault.pay_sol {
+            transfer_vault_lamports(reward_vault, &ctx.accounts.recipient.to_account_info(), total)?;
+        } else {
+            pay_out_tokens(
+                reward_vault,
+                ctx.accounts.vault_token_account.as_ref(),
+                ctx.accounts.recipient_token_account.as_ref(),
+                recipient,
+                &ctx.accounts.token_program,
+                total,
+            )?;
+        }
+
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &payouts,
+            0,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        Ok(())
+    }
+
+    /**
+     * Set the vault treasury
+     * 
+     * Changes where swept funds are sent. Defaults to the admin at init.
+     * 
+     * @param ctx - Context containing admin and vault accounts
+     * @param treasury - Destination for swept funds
+     */
+    pub fn set_treasury(ctx: Context<UpdateVault>, treasury: Pubkey) -> Result<()> {
+        ctx.accounts.reward_vault.treasury = treasury;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Activate a pending epoch
+     * 
+     * Opens an epoch before its start timestamp. Epochs become Active on
+     * their own at start_ts, so this is only needed to start one early.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     */
+    pub fn activate_epoch(ctx: Context<UpdateEpoch>) -> Result<()> {
+        let epoch = &mut ctx.accounts.epoch;
+        let now = Clock::get()?.unix_timestamp;
+        require!(epoch.effective_status(now) == EpochStatus::Pending, RewardVaultError::InvalidEpochStatus);
+
+        epoch.status = EpochStatus::Active;
+
+        Ok(())
+    }
+
+    /**
+     * Finalize an active epoch
+     * 
+     * Moves an epoch from Active to Ended, closing it to further
+     * disbursements before its end_ts.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     */
+    pub fn finalize_epoch(ctx: Context<UpdateEpoch>) -> Result<()> {
+        let epoch = &mut ctx.accounts.epoch;
+        let now = Clock::get()?.unix_timestamp;
+        require!(epoch.effective_status(now) == EpochStatus::Active, RewardVaultError::InvalidEpochStatus);
+
+        epoch.status = EpochStatus::Ended;
+
+        Ok(())
+    }
+
+    /**
+     * Close an epoch whose window has elapsed
+     * 
+     * Stores Ended on an epoch past its end_ts, so clients reading the
+     * account see the status it already has. Use finalize_epoch to end an
+     * epoch early.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     */
+    pub fn close_epoch(ctx: Context<UpdateEpoch>) -> Result<()> {
+        let epoch = &mut ctx.accounts.epoch;
+        require!(epoch.status != EpochStatus::Swept, RewardVaultError::EpochAlreadySwept);
+        require!(epoch.status != EpochStatus::Ended, RewardVaultError::InvalidEpochStatus);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= epoch.end_ts, RewardVaultError::EpochNotEnded);
+
+        epoch.status = EpochStatus::Ended;
+
+        Ok(())
+    }
+
+    /**
+     * Split a SOL reward across weighted recipients
+     * 
+     * Divides `total`, scaled by the epoch's reward multiplier, between the
+     * recipients in `remaining_accounts` in proportion to `weights`. Rounding
+     * dust goes to the last recipient so exactly the scaled total leaves the
+     * vault. Recipients are passed and checked, and each share pays the
+     * protocol fee, as in `disburse_sol_batch`.
+     * 
+     * @param ctx - Context containing disbursement accounts and recipients
+     * @param total - Total amount in lamports to split
+     * @param weights - Relative weight for each recipient, in order
+     * @param proofs - Allowlist proof for each recipient, in order (empty = epoch has no allowlist)
+     */
+    pub fn disburse_split<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DisburseSolBatch<'info>>,
+        total: u64,
+        weights: Vec<u16>,
+        proofs: Vec<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        require!(total > 0, RewardVaultError::InvalidAmount);
+
+        // Run the batch-wide checks shared with disburse_sol_batch
+        let now = Clock::get()?.unix_timestamp;
+        let checks = DisbursementChecks {
+            reward_vault: &ctx.accounts.reward_vault,
+            epoch: ctx.accounts.epoch.as_ref(),
+            distributor_signer: &ctx.accounts.distributor_signer,
+            session_key: ctx.accounts.session_key.as_ref(),
+            admin_signer: ctx.accounts.admin_signer.as_ref(),
+            blocklist: ctx.accounts.blocklist.as_ref(),
+            recipient_stats: None,
+        };
+        check_batch_disbursement(&checks, true, ctx.remaining_accounts, weights.len(), &proofs, now)?;
+
+        // Scale before splitting so the shares add up to the boosted total
+        let total = apply_reward_multiplier(checks.epoch, total)?;
+        let shares = split_by_weight(total, &weights)?;
+        for share in &shares {
+            check_disbursement_amount(checks.reward_vault, *share)?;
+        }
+
+        log_compute_units(&ctx.accounts.reward_vault, "batch payout start");
+        let (payouts, fees) = pay_out_sol_batch(&ctx, &checks, &shares, &proofs, now)?;
+        log_compute_units(&ctx.accounts.reward_vault, "batch payout end");
+        record_epoch_disbursement(ctx.accounts.epoch.as_mut(), total)?;
+
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &payouts,
+            fees,
+            now,
+        )?;
+        ctx.accounts.reward_vault.last_disburse_ts = now;
+
+        if ctx.accounts.reward_vault.emit_events {
+            let vault_balance_after = vault_balance_after(&ctx.accounts.reward_vault, None)?;
+            for (recipient, amount) in payouts {
+                emit!(RewardDisbursed {
+                    vault: ctx.accounts.reward_vault.key(),
+                    recipient,
+                    amount,
+                    memo: None,
+                    vault_balance_after,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Cancel an epoch created in error
+     * 
+     * Closes an epoch that has not paid anything out, returning its rent to
+     * the admin. Epochs split into sub-epochs cannot be cancelled, since
+     * their shards pay out independently. Funding tracked against the
+     * epoch stays in the vault; funders get their tracked contributions
+     * back through reclaim_contribution.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     */
+    pub fn cancel_epoch(ctx: Context<CancelEpoch>) -> Result<()> {
+        let epoch = &ctx.accounts.epoch;
+        require!(epoch.total_disbursed == 0, RewardVaultError::EpochHasDisbursements);
+        require!(epoch.sub_epoch_count == 0, RewardVaultError::EpochHasSubEpochs);
+
+        emit!(EpochCancelled {
+            vault: ctx.accounts.reward_vault.key(),
+            epoch_index: epoch.index,
+            total_funded: epoch.total_funded,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * Set the per-recipient cooldown
+     * 
+     * Sets the minimum number of seconds between payouts to the same
+     * recipient, across epochs. Zero disables the cooldown.
+     * 
+     * @param ctx - Context containing admin or operator and vault accounts
+     * @param recipient_cooldown - Minimum seconds between payouts to one recipient
+     */
+    pub fn set_recipient_cooldown(ctx: Context<ConfigureVault>, recipient_cooldown: i64) -> Result<()> {
+        require!(recipient_cooldown >= 0, RewardVaultError::InvalidCooldown);
+        ctx.accounts.reward_vault.recipient_cooldown = recipient_cooldown;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Rotate the vault distributor
+     * 
+     * Replaces the vault-level distributor key, e.g. after a key compromise.
+     * Epochs with their own distributor override are unaffected.
+     * 
+     * @param ctx - Context containing admin or operator and vault accounts
+     * @param distributor - Public key of the new distributor
+     */
+    pub fn set_distributor(ctx: Context<ConfigureVault>, distributor: Pubkey) -> Result<()> {
+        ctx.accounts.reward_vault.distributor = distributor;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Set the SOL reserve
+     * 
+     * Keeps a buffer above rent in SOL vaults: payouts must leave at least
+     * the rent-exempt minimum plus this reserve in the vault.
+     * 
+     * @param ctx - Context containing admin or operator and vault accounts
+     * @param sol_reserve - Lamports kept above the rent-exempt minimum (0 = rent only)
+     */
+    pub fn set_sol_reserve(ctx: Context<ConfigureVault>, sol_reserve: u64) -> Result<()> {
+        ctx.accounts.reward_vault.sol_reserve = sol_reserve;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Set funded-epoch strict mode
+     * 
+     * When enabled, epoch-tied disbursements must pass an epoch that has
+     * received funding. Guards against paying out of a vault whose funds
+     * were recorded against the wrong epoch.
+     * 
+     * @param ctx - Context containing admin or operator and vault accounts
+     * @param require_funded_epoch - Whether disbursements need a funded epoch
+     */
+    pub fn set_require_funded_epoch(ctx: Context<ConfigureVault>, require_funded_epoch: bool) -> Result<()> {
+        ctx.accounts.reward_vault.require_funded_epoch = require_funded_epoch;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Set vault metadata
+     * 
+     * Labels the vault for front-ends with a display name and a metadata
+     * URI, both UTF-8 and stored zero-padded.
+     * 
+     * @param ctx - Context containing admin or operator and vault accounts
+     * @param metadata - UTF-8 display name and metadata URI
+     */
+    pub fn set_metadata(ctx: Context<ConfigureVault>, metadata: VaultMetadata) -> Result<()> {
+        let reward_vault = &mut ctx.accounts.reward_vault;
+        reward_vault.name = encode_metadata(&metadata.name)?;
+        reward_vault.metadata_uri = encode_metadata(&metadata.metadata_uri)?;
+        reward_vault.refresh_config_hash();
+
+        emit!(MetadataUpdated {
+            vault: reward_vault.key(),
+            name: String::from_utf8(metadata.name).map_err(|_| RewardVaultError::InvalidMetadata)?,
+            metadata_uri: String::from_utf8(metadata.metadata_uri).map_err(|_| RewardVaultError::InvalidMetadata)?,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * Verify the vault configuration hash
+     * 
+     * Lets monitors assert that the vault's configuration still matches a
+     * known commitment, failing if any admin change has drifted it.
+     * 
+     * @param ctx - Context containing the vault account
+     * @param expected - Expected keccak hash of the vault configuration
+     */
+    pub fn verify_config(ctx: Context<VerifyConfig>, expected: [u8; 32]) -> Result<()> {
+        require!(
+            ctx.accounts.reward_vault.config_hash == expected,
+            RewardVaultError::ConfigHashMismatch
+        );
+
+        Ok(())
+    }
+
+    /**
+     * Initialize a per-epoch vault token account
+     * 
+     * Creates a token account PDA owned by the vault that holds rewards for
+     * a single epoch. SPL disbursements tied to the epoch pay from it when
+     * supplied instead of the shared vault token account.
+     * 
+     * @param ctx - Context containing admin, vault, epoch, and mint accounts
+     */
+    pub fn init_epoch_vault(ctx: Context<InitEpochVault>) -> Result<()> {
+        require!(!ctx.accounts.reward_vault.pay_sol, RewardVaultError::WrongPayoutMode);
+
+        Ok(())
+    }
+
+    /**
+     * Start several epochs at once
+     * 
+     * Creates a run of epochs in one transaction, e.g. a season of weekly
+     * epochs. Indices must be sequential and windows must not overlap.
+     * Epoch PDAs are supplied as writable remaining accounts, one per spec
+     * in the same order.
+     * 
+     * @param ctx - Context containing admin and vault accounts
+     * @param specs - Epoch parameters, ordered by index
+     */
+    pub fn start_epochs<'info>(
+        ctx: Context<'_, '_, 'info, 'info, StartEpochs<'info>>,
+        specs: Vec<EpochSpec>,
+    ) -> Result<()> {
+        require!(
+            !specs.is_empty() && specs.len() == ctx.remaining_accounts.len(),
+            RewardVaultError::BatchLengthMismatch
+        );
+        require!(specs.len() <= RewardVault::MAX_EPOCH_BATCH, RewardVaultError::BatchTooLarge);
+
+        // Validate the whole batch before creating any accounts
+        let now = Clock::get()?.unix_timestamp;
+        for (i, spec) in specs.iter().enumerate() {
+            validate_epoch_spec(&ctx.accounts.reward_vault, spec, now)?;
+            if i > 0 {
+                let prev = &specs[i - 1];
+                require!(prev.index.checked_add(1) == Some(spec.index), RewardVaultError::NonSequentialEpochIndex);
+                require!(spec.start_ts >= prev.end_ts, RewardVaultError::OverlappingEpoch);
+            }
+        }
+
+        for (spec, epoch_info) in specs.iter().zip(ctx.remaining_accounts.iter()) {
+            create_epoch_account(&ctx, epoch_info, spec)?;
+            record_epoch_window(&mut ctx.accounts.reward_vault, spec.end_ts);
+
+            emit!(NewEpoch {
+                start_ts: spec.start_ts,
+                end_ts: spec.end_ts,
+                epoch_index: spec.index,
+            });
+        }
+
+        if let Some(vault_stats) = active_vault_stats(&ctx.accounts.reward_vault, ctx.accounts.vault_stats.as_mut())? {
+            vault_stats.record_epochs(specs.len() as u64)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Set non-overlapping epoch enforcement
+     * 
+     * When enabled, every new epoch must start at or after the end of the
+     * latest epoch the vault has created, so at most one epoch covers any
+     * point in time.
+     * 
+     * @param ctx - Context containing admin or operator and vault accounts
+     * @param non_overlapping_epochs - Whether to reject overlapping epoch windows
+     */
+    pub fn set_non_overlapping_epochs(ctx: Context<ConfigureVault>, non_overlapping_epochs: bool) -> Result<()> {
+        ctx.accounts.reward_vault.non_overlapping_epochs = non_overlapping_epochs;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Configure a fixed per-recipient payout
+     * 
+     * Sets the exact amount each recipient receives from the epoch through
+     * disburse_fixed and how many such payouts remain.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     * @param per_recipient_amount - Amount paid to each recipient
+     * @param slots - Number of fixed payouts available
+     */
+    pub fn set_fixed_payout(ctx: Context<UpdateEpoch>, per_recipient_amount: u64, slots: u64) -> Result<()> {
+        check_disbursement_amount(&ctx.accounts.reward_vault, per_recipient_amount)?;
+
+        let epoch = &mut ctx.accounts.epoch;
+        epoch.per_recipient_amount = per_recipient_amount;
+        epoch.remaining_slots = slots;
+
+        Ok(())
+    }
+
+    /**
+     * Disburse the epoch's fixed payout
+     * 
+     * Pays exactly the epoch's configured per-recipient amount, so the
+     * distributor cannot vary amounts in equal-split events. Each payout
+     * consumes one slot.
+     * 
+     * @param ctx - Context containing vault, epoch, and recipient accounts
+     * @param proof - Merkle proof of the recipient against the epoch allowlist
+     * @param fee_exempt_proof - Proof of the recipient against the fee-exempt root, if exempt
+     */
+    pub fn disburse_fixed(
+        ctx: Context<DisburseFixed>,
+        proof: Vec<[u8; 32]>,
+        fee_exempt_proof: Option<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        let amount = ctx.accounts.epoch.per_recipient_amount;
+        check_disbursement_amount(&ctx.accounts.reward_vault, amount)?;
+        check_memo(&ctx.accounts.reward_vault, None)?;
+        require!(ctx.accounts.epoch.remaining_slots > 0, RewardVaultError::EpochFullyPaid);
+
+        // Verify distributor authorization
+        let epoch = Some(&ctx.accounts.epoch);
+        authorize_payout(
+            &ctx.accounts.reward_vault,
+            epoch,
+            &ctx.accounts.distributor_signer,
+            ctx.accounts.session_key.as_ref(),
+            ctx.accounts.admin_signer.as_ref(),
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        check_disburse_cooldown(&ctx.accounts.reward_vault, now)?;
+        check_recipient_cooldown(&ctx.accounts.reward_vault, &ctx.accounts.recipient_stats, now)?;
+        check_epoch_active(epoch)?;
+        check_epoch_funded(&ctx.accounts.reward_vault, epoch)?;
+
+        let recipient = ctx.accounts.recipient.key();
+        check_recipient_allowed(epoch, ctx.accounts.reward_vault.key(), recipient, &proof)?;
+        check_not_blocked(&ctx.accounts.reward_vault, ctx.accounts.blocklist.as_ref(), recipient)?;
+
+        log_compute_units(&ctx.accounts.reward_vault, "payout start");
+        // Route payout based on vault configuration
+        let reward_vault = &ctx.accounts.reward_vault;
+        let fee = protocol_fee(reward_vault, recipient, amount, fee_exempt_proof.as_deref())?;
+        let payout = amount.checked_sub(fee).ok_or(RewardVaultError::ArithmeticOverflow)?;
+        if reward_vault.pay_sol {
+            let balance = reward_vault.to_account_info().lamports();
+            check_payout_balance(reward_vault, balance, amount)?;
+            transfer_vault_lamports(reward_vault, &ctx.accounts.recipient.to_account_info(), payout)?;
+            pay_fee_sol(reward_vault, ctx.accounts.treasury.as_ref(), fee)?;
+        } else {
+            let vault_token = ctx
+                .accounts
+                .vault_token_account
+                .as_ref()
+                .ok_or(RewardVaultError::VaultTokenRequired)?;
+
+            let recipient_token = ctx
+                .accounts
+                .recipient_token_account
+                .as_ref()
+                .ok_or(RewardVaultError::RecipientTokenRequired)?;
+            require_keys_eq!(recipient_token.owner, recipient, RewardVaultError::RecipientMismatch);
+
+            check_payout_balance(reward_vault, vault_token.amount, amount)?;
+            transfer_vault_tokens(reward_vault, vault_token, recipient_token, &ctx.accounts.token_program, payout)?;
+            pay_fee_spl(
+                reward_vault,
+                vault_token,
+                ctx.accounts.treasury_token_account.as_ref(),
+                &ctx.accounts.token_program,
+                fee,
+            )?;
+        }
+        log_compute_units(&ctx.accounts.reward_vault, "payout end");
+
+        let epoch = &mut ctx.accounts.epoch;
+        epoch.remaining_slots -= 1;
+        record_epoch_disbursement(Some(epoch), amount)?;
+
+        record_recipient_payout(
+            &mut ctx.accounts.recipient_stats,
+            ctx.accounts.reward_vault.key(),
+            recipient,
+            payout,
+            ctx.bumps.recipient_stats,
+        )?;
+
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &[(recipient, payout)],
+            fee,
+            now,
+        )?;
+        ctx.accounts.reward_vault.last_disburse_ts = now;
+
+        if ctx.accounts.reward_vault.emit_events {
+            emit!(RewardDisbursed {
+                vault: ctx.accounts.reward_vault.key(),
+                recipient,
+                amount: payout,
+                memo: None,
+                vault_balance_after: vault_balance_after(&ctx.accounts.reward_vault, ctx.accounts.vault_token_account.as_ref().map(AsRef::as_ref))?,
+            });
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Set event emission
+     * 
+     * Toggles RewardDisbursed and VaultFunded events. Admins running dense
+     * disbursement batches can turn them off to save compute; state
+     * updates are unaffected.
+     * 
+     * @param ctx - Context containing admin or operator and vault accounts
+     * @param emit_events - Whether to emit disbursement and funding events
+     */
+    pub fn set_emit_events(ctx: Context<ConfigureVault>, emit_events: bool) -> Result<()> {
+        ctx.accounts.reward_vault.emit_events = emit_events;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Close a reward vault
+     * 
+     * Retires an emptied vault. The vault's entire lamport balance, rent
+     * plus any stray SOL sent to the PDA, is moved to the admin before the
+     * account is handed back to the system program. Fails while any escrow
+     * is still open, since closing would orphan it.
+     * 
+     * With `sweep_all`, the unspent balance of every epoch passed as a
+     * writable remaining account is first swept to the treasury, regardless
+     * of the epoch's status or grace window. Each epoch must be followed by
+     * all of its sub-epochs. The sweep respects the SOL reserve floor, so a
+     * configured `sol_reserve` must be cleared before the vault can close.
+     * 
+     * @param ctx - Context containing admin, vault, treasury, and token accounts
+     * @param sweep_all - Whether to sweep the supplied epochs before closing
+     */
+    pub fn close_vault<'info>(ctx: Context<'_, '_, 'info, 'info, CloseVault<'info>>, sweep_all: bool) -> Result<()> {
+        if sweep_all {
+            sweep_epochs_on_close(&ctx)?;
+            if let Some(vault_token) = ctx.accounts.vault_token_account.as_mut() {
+                vault_token.reload()?;
+            }
+        }
+
+        // Open escrows would be orphaned by closing the vault
+        require!(
+            ctx.accounts.reward_vault.outstanding_obligations == 0,
+            RewardVaultError::OutstandingObligations
+        );
+
+        // Reward balances must be paid out or swept first
+        ensure_vault_empty(&ctx.accounts.reward_vault, ctx.accounts.vault_token_account.as_ref())?;
+
+        let reward_vault_info = ctx.accounts.reward_vault.to_account_info();
+        let admin_info = ctx.accounts.admin.to_account_info();
+
+        // Move every lamport, not just rent, then zero the account
+        move_lamports(&reward_vault_info, &admin_info, reward_vault_info.lamports())?;
+        reward_vault_info.assign(&system_program::ID);
+        reward_vault_info.realloc(0, false)?;
+
+        Ok(())
+    }
+
+    /**
+     * Refund a disbursement to the vault
+     * 
+     * First-class path for returning a mistaken payout: the recipient signs
+     * a transfer back to the vault, co-signed by the distributor, and the
+     * epoch's disbursed total, the recipient's lifetime earnings, and the
+     * vault's all-time disbursed total are reduced accordingly.
+     * 
+     * @param ctx - Context containing recipient, distributor, vault, epoch, and stats accounts
+     * @param amount - Amount being returned
+     * @param epoch_index - Index of the epoch the payout was made against
+     */
+    pub fn refund_to_vault(ctx: Context<RefundToVault>, amount: u64, epoch_index: u64) -> Result<()> {
+        require!(amount > 0, RewardVaultError::InvalidAmount);
+
+        // Refunds adjust epoch accounting, so the distributor must approve
+        authorize_distributor(
+            &ctx.accounts.reward_vault,
+            Some(&ctx.accounts.epoch),
+            &ctx.accounts.distributor_signer,
+            ctx.accounts.session_key.as_ref(),
+        )?;
+
+        let recipient = ctx.accounts.recipient.to_account_info();
+        let reward_vault = &ctx.accounts.reward_vault;
+        if reward_vault.pay_sol {
+            let reward_vault_info = reward_vault.to_account_info();
+            let system_program = ctx.accounts.system_program.to_account_info();
+
+            let transfer_ix = system_instruction::transfer(recipient.key, reward_vault_info.key, amount);
+            invoke(&transfer_ix, &[recipient.clone(), reward_vault_info, system_program])?;
+        } else {
+            let reward_mint = reward_vault
+                .reward_mint
+                .ok_or(RewardVaultError::RewardMintRequired)?;
+
+            let vault_token = ctx
+                .accounts
+                .vault_token_account
+                .as_ref()
+                .ok_or(RewardVaultError::VaultTokenRequired)?;
+
+            let recipient_token = ctx
+                .accounts
+                .recipient_token_account
+                .as_ref()
+                .ok_or(RewardVaultError::RecipientTokenRequired)?;
+
+            require_keys_eq!(vault_token.key(), vault_token_address(reward_vault, reward_mint), RewardVaultError::InvalidVaultTokenAccount);
+            require_keys_eq!(recipient_token.mint, reward_mint, RewardVaultError::MintMismatch);
+
+            let cpi_accounts = Transfer {
+                from: recipient_token.to_account_info(),
+                to: vault_token.to_account_info(),
+                authority: recipient.clone(),
+            };
+            token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), amount)?;
+        }
+
+        let epoch = &mut ctx.accounts.epoch;
+        epoch.total_disbursed = epoch.total_disbursed.saturating_sub(u128::from(amount));
+
+        // Take the refund back out of the payout counters
+        if let Some(recipient_stats) = ctx.accounts.recipient_stats.as_mut() {
+            recipient_stats.record_refund(amount);
+        }
+        if let Some(vault_stats) = active_vault_stats(&ctx.accounts.reward_vault, ctx.accounts.vault_stats.as_mut())? {
+            vault_stats.record_refund(amount);
+        }
+
+        emit!(RewardRefunded {
+            vault: ctx.accounts.reward_vault.key(),
+            recipient: recipient.key(),
+            epoch_index,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * Get authorized distributors
+     * 
+     * Read-only view listing the keys allowed to disburse, so clients need
+     * not parse the raw account layout. The vault-level distributor comes
+     * first, followed by the epoch's override when an epoch is supplied.
+     * Delivered to clients as the instruction's return data.
+     * 
+     * @param ctx - Context containing the vault and optional epoch accounts
+     */
+    pub fn get_distributors(ctx: Context<GetDistributors>) -> Result<Vec<Pubkey>> {
+        let mut distributors = vec![ctx.accounts.reward_vault.distributor];
+
+        if let Some(epoch) = ctx.accounts.epoch.as_ref() {
+            require_keys_eq!(epoch.vault, ctx.accounts.reward_vault.key(), RewardVaultError::EpochMismatch);
+            if let Some(epoch_distributor) = epoch.epoch_distributor {
+                if !distributors.contains(&epoch_distributor) {
+                    distributors.push(epoch_distributor);
+                }
+            }
+        }
+
+        Ok(distributors)
+    }
+
+    /**
+     * Escrow a reward behind a challenge period
+     * 
+     * For disputed results: moves the reward out of the vault into an
+     * escrow PDA that the admin can challenge until the deadline. Once the
+     * deadline passes unchallenged, anyone may finalize it to the recipient.
+     * 
+     * @param ctx - Context containing vault, distributor, and escrow accounts
+     * @param recipient - Wallet that receives the reward on finalization
+     * @param amount - Amount to escrow in lamports
+     * @param challenge_until - Timestamp until which the admin may challenge
+     */
+    pub fn escrow_reward(
+        ctx: Context<EscrowReward>,
+        recipient: Pubkey,
+        amount: u64,
+        challenge_until: i64,
+    ) -> Result<()> {
+        check_disbursement_amount(&ctx.accounts.reward_vault, amount)?;
+        check_memo(&ctx.accounts.reward_vault, None)?;
+        require!(ctx.accounts.reward_vault.pay_sol, RewardVaultError::WrongPayoutMode);
+
+        // Verify distributor authorization
+        authorize_payout(
+            &ctx.accounts.reward_vault,
+            None,
+            &ctx.accounts.distributor_signer,
+            ctx.accounts.session_key.as_ref(),
+            ctx.accounts.admin_signer.as_ref(),
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(challenge_until > now, RewardVaultError::InvalidChallengeDeadline);
+        check_not_blocked(&ctx.accounts.reward_vault, ctx.accounts.blocklist.as_ref(), recipient)?;
+
+        // Hold the reward in the escrow account until finalized or challenged
+        let balance = ctx.accounts.reward_vault.to_account_info().lamports();
+        check_payout_balance(&ctx.accounts.reward_vault, balance, amount)?;
+        let escrow_info = ctx.accounts.escrowed_reward.to_account_info();
+        transfer_vault_lamports(&ctx.accounts.reward_vault, &escrow_info, amount)?;
+
+        let escrowed_reward = &mut ctx.accounts.escrowed_reward;
+        escrowed_reward.vault = ctx.accounts.reward_vault.key();
+        escrowed_reward.recipient = recipient;
+        escrowed_reward.amount = amount;
+        escrowed_reward.challenge_until = challenge_until;
+        escrowed_reward.payer = ctx.accounts.payer.key();
+        escrowed_reward.bump = ctx.bumps.escrowed_reward;
+
+        let reward_vault = &mut ctx.accounts.reward_vault;
+        reward_vault.outstanding_obligations = reward_vault
+            .outstanding_obligations
+            .checked_add(1)
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+
+        emit!(RewardEscrowed {
+            vault: escrowed_reward.vault,
+            recipient,
+            amount,
+            challenge_until,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * Challenge an escrowed reward
+     * 
+     * Cancels an escrow before its deadline, returning the reward to the
+     * vault. The escrow's rent goes back to whoever paid for it.
+     * 
+     * @param ctx - Context containing admin, vault, and escrow accounts
+     */
+    pub fn challenge_reward(ctx: Context<ChallengeReward>) -> Result<()> {
+        let escrowed_reward = &ctx.accounts.escrowed_reward;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < escrowed_reward.challenge_until, RewardVaultError::ChallengePeriodEnded);
+
+        move_lamports(
+            &escrowed_reward.to_account_info(),
+            &ctx.accounts.reward_vault.to_account_info(),
+            escrowed_reward.amount,
+        )?;
+        settle_obligation(&mut ctx.accounts.reward_vault);
+
+        emit!(RewardChallenged {
+            vault: escrowed_reward.vault,
+            recipient: escrowed_reward.recipient,
+            amount: escrowed_reward.amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * Finalize an escrowed reward
+     * 
+     * Pays an unchallenged escrow to its recipient once the challenge
+     * period has passed. Callable by anyone.
+     * 
+     * @param ctx - Context containing vault, escrow, and recipient accounts
+     */
+    pub fn finalize_reward(ctx: Context<FinalizeReward>) -> Result<()> {
+        let escrowed_reward = &ctx.accounts.escrowed_reward;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= escrowed_reward.challenge_until, RewardVaultError::ChallengePeriodActive);
+        check_not_blocked(&ctx.accounts.reward_vault, ctx.accounts.blocklist.as_ref(), escrowed_reward.recipient)?;
+
+        let amount = escrowed_reward.amount;
+        move_lamports(
+            &escrowed_reward.to_account_info(),
+            &ctx.accounts.recipient.to_account_info(),
+            amount,
+        )?;
+        settle_obligation(&mut ctx.accounts.reward_vault);
+
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &[(escrowed_reward.recipient, amount)],
+            0,
+            now,
+        )?;
+
+        if ctx.accounts.reward_vault.emit_events {
+            emit!(RewardDisbursed {
+                vault: escrowed_reward.vault,
+                recipient: escrowed_reward.recipient,
+                amount,
+                memo: None,
+                vault_balance_after: vault_balance_after(&ctx.accounts.reward_vault, None)?,
+            });
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Withdraw a funding contribution
+     * 
+     * Lets a funder who over-committed to an epoch pull back part of their
+     * tracked contribution, as long as the epoch has not started yet.
+     * 
+     * @param ctx - Context containing funder, vault, epoch, and contribution accounts
+     * @param amount - Amount to withdraw
+     */
+    pub fn withdraw_contribution(ctx: Context<WithdrawContribution>, amount: u64) -> Result<()> {
+        require!(amount > 0, RewardVaultError::InvalidAmount);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < ctx.accounts.epoch.start_ts, RewardVaultError::EpochAlreadyStarted);
+        require!(!ctx.accounts.epoch.funding_locked, RewardVaultError::FundingLocked);
+        require!(!ctx.accounts.epoch.rolled_over, RewardVaultError::EpochAlreadyRolledOver);
+
+        let contribution = &mut ctx.accounts.funder_contribution;
+        require!(amount <= contribution.amount, RewardVaultError::ContributionExceeded);
+        contribution.amount -= amount;
+
+        let epoch = &mut ctx.accounts.epoch;
+        epoch.total_funded = epoch.total_funded.saturating_sub(u128::from(amount));
+        if let Some(vault_stats) = active_vault_stats(&ctx.accounts.reward_vault, ctx.accounts.vault_stats.as_mut())? {
+            vault_stats.record_withdrawal(amount);
+        }
+
+        // Return the funds the same way they arrived
+        let reward_vault = &ctx.accounts.reward_vault;
+        if reward_vault.pay_sol {
+            transfer_vault_lamports(reward_vault, &ctx.accounts.funder.to_account_info(), amount)?;
+        } else {
+            let vault_token = ctx
+                .accounts
+                .vault_token_account
+                .as_ref()
+                .ok_or(RewardVaultError::VaultTokenRequired)?;
+
+            let funder_token = ctx
+                .accounts
+                .funder_token_account
+                .as_ref()
+                .ok_or(RewardVaultError::FunderTokenRequired)?;
+
+            transfer_vault_tokens(reward_vault, vault_token, funder_token, &ctx.accounts.token_program, amount)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Reclaim a contribution to a cancelled epoch
+     * 
+     * Once cancel_epoch has closed an epoch, its funding is no longer
+     * tracked anywhere, so funders pull back their whole outstanding
+     * contribution here. The contribution account is closed to the funder.
+     * 
+     * @param ctx - Context containing funder, vault, cancelled epoch, and contribution accounts
+     * @param epoch_index - Index of the cancelled epoch
+     */
+    pub fn reclaim_contribution(ctx: Context<ReclaimContribution>, _epoch_index: u64) -> Result<()> {
+        require!(ctx.accounts.epoch.data_is_empty(), RewardVaultError::EpochNotCancelled);
+
+        let amount = ctx.accounts.funder_contribution.amount;
+        ctx.accounts.funder_contribution.amount = 0;
+        if amount == 0 {
+            return Ok(());
+        }
+        if let Some(vault_stats) = active_vault_stats(&ctx.accounts.reward_vault, ctx.accounts.vault_stats.as_mut())? {
+            vault_stats.record_withdrawal(amount);
+        }
+
+        // Return the funds the same way they arrived
+        let reward_vault = &ctx.accounts.reward_vault;
+        if reward_vault.pay_sol {
+            transfer_vault_lamports(reward_vault, &ctx.accounts.funder.to_account_info(), amount)?;
+        } else {
+            let vault_token = ctx
+                .accounts
+                .vault_token_account
+                .as_ref()
+                .ok_or(RewardVaultError::VaultTokenRequired)?;
+
+            let funder_token = ctx
+                .accounts
+                .funder_token_account
+                .as_ref()
+                .ok_or(RewardVaultError::FunderTokenRequired)?;
+
+            transfer_vault_tokens(reward_vault, vault_token, funder_token, &ctx.accounts.token_program, amount)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Migrate an epoch to the current layout
+     * 
+     * Epochs created before fields such as total_disbursed, status, and
+     * funding_cap were added still have the original smaller layout and
+     * live at the original `[b"epoch", vault]` address. This creates the
+     * epoch at its indexed address with the current layout, carries over
+     * the original fields, zero-initializes the new ones, and stamps the
+     * current version. The old account is closed with its rent returned to
+     * the admin, who pays rent for the new one.
+     * 
+     * @param ctx - Context containing admin, vault, legacy epoch, and new epoch accounts
+     */
+    pub fn migrate_epoch(ctx: Context<MigrateEpoch>) -> Result<()> {
+        let legacy_info = ctx.accounts.legacy_epoch.to_account_info();
+        let epoch_info = ctx.accounts.epoch.to_account_info();
+
+        // An epoch on the current layout at the target means this already ran
+        if !epoch_info.data_is_empty() {
+            let epoch = Epoch::try_deserialize(&mut &epoch_info.try_borrow_data()?[..])?;
+            require!(epoch.version < Epoch::VERSION, RewardVaultError::AlreadyMigrated);
+            return err!(RewardVaultError::InvalidAccountLayout);
+        }
+        require_keys_eq!(*legacy_info.owner, crate::ID, RewardVaultError::InvalidAccountLayout);
+
+        let legacy = {
+            let data = legacy_info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == Epoch::DISCRIMINATOR,
+                RewardVaultError::InvalidAccountLayout
+            );
+            require!(data.len() == EpochV0::LEN, RewardVaultError::InvalidAccountLayout);
+            EpochV0::deserialize(&mut &data[8..])?
+        };
+
+        let vault_key = ctx.accounts.reward_vault.key();
+        require_keys_eq!(legacy.vault, vault_key, RewardVaultError::EpochMismatch);
+
+        let (expected, bump) = Epoch::find_address(&vault_key, legacy.index);
+        require_keys_eq!(epoch_info.key(), expected, RewardVaultError::EpochMismatch);
+
+        // Create the indexed epoch account signed by its PDA seeds
+        let index_seed = Epoch::index_seed(legacy.index);
+        let seeds: [&[u8]; 4] = [Epoch::SEED, vault_key.as_ref(), &index_seed, &[bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = CreateAccount {
+            from: ctx.accounts.admin.to_account_info(),
+            to: epoch_info.clone(),
+        };
+        system_program::create_account(
+            CpiContext::new_with_signer(ctx.accounts.system_program.to_account_info(), cpi_accounts, signer),
+            Rent::get()?.minimum_balance(Epoch::LEN),
+            Epoch::LEN as u64,
+            &crate::ID,
+        )?;
+
+        legacy.migrate(bump).try_serialize(&mut &mut epoch_info.try_borrow_mut_data()?[..])?;
+
+        // Close the legacy account
+        let admin_info = ctx.accounts.admin.to_account_info();
+        move_lamports(&legacy_info, &admin_info, legacy_info.lamports())?;
+        legacy_info.assign(&system_program::ID);
+        legacy_info.realloc(0, false)?;
+
+        emit!(EpochMigrated {
+            vault: vault_key,
+            epoch_index: legacy.index,
+            legacy_address: legacy_info.key(),
+            address: expected,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * Migrate a vault to the current layout
+     * 
+     * Upgrades a vault created with an older layout (V0 or V1) to
+     * RewardVault::LEN, carrying over its existing fields, defaulting the
+     * new ones as init_vault would, and stamping the current version. Vaults already on
+     * the current or a newer version are refused. The admin pays any extra
+     * rent.
+     * 
+     * @param ctx - Context containing admin and vault accounts
+     */
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        let vault_info = ctx.accounts.reward_vault.to_account_info();
+        require_keys_eq!(*vault_info.owner, crate::ID, RewardVaultError::InvalidAccountLayout);
+
+        let mut reward_vault = {
+            let data = vault_info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == RewardVault::DISCRIMINATOR,
+                RewardVaultError::InvalidAccountLayout
+            );
+
+            // Already on the current layout; refuse to migrate again or downgrade
+            if data.len() == RewardVault::LEN {
+                let reward_vault = RewardVault::try_deserialize(&mut &data[..])?;
+                require!(reward_vault.version < RewardVault::VERSION, RewardVaultError::AlreadyMigrated);
+            }
+
+            if data.len() == RewardVaultV0::LEN {
+                let legacy = RewardVaultV0::deserialize(&mut &data[8..])?;
+                RewardVault {
+                    admin: legacy.admin,
+                    distributor: legacy.distributor,
+                    reward_mint: legacy.reward_mint,
+                    pay_sol: legacy.pay_sol,
+                    min_disbursement: 0,
+                    funder_allowlist_root: [0u8; 32],
+                    max_epochs: 0,
+                    disburse_cooldown: 0,
+                    last_disburse_ts: 0,
+                    treasury: legacy.admin,
+                    recipient_cooldown: 0,
+                    non_overlapping_epochs: false,
+                    last_epoch_end: 0,
+                    emit_events: true,
+                    mint_decimals: resolve_mint_decimals(legacy.pay_sol, legacy.reward_mint, ctx.accounts.mint.as_ref())?,
+                    operator: None,
+                    paused: false,
+                    blocklist_active: false,
+                    require_admin_cosign: false,
+                    max_relayer_fee: 0,
+                    reward_tiers: Vec::new(),
+                    audit_log_active: false,
+                    sol_reserve: 0,
+                    stats_active: false,
+                    require_funded_epoch: false,
+                    debug_profiling: false,
+                    outstanding_obligations: 0,
+                    fee_bps: 0,
+                    fee_exempt_root: [0u8; 32],
+                    epoch_cadence: 0,
+                    max_disburse_bps: RewardVault::FULL_DISBURSE_BPS,
+                    require_memo: false,
+                    name: [0u8; 32],
+                    metadata_uri: [0u8; 128],
+                    config_hash: [0u8; 32],
+                    version: RewardVault::VERSION,
+                    bump: legacy.bump,
+                }
+            } else if data.len() == RewardVaultV1::LEN {
+                RewardVaultV1::deserialize(&mut &data[8..])?.upgrade()
+            } else {
+                return err!(RewardVaultError::InvalidAccountLayout);
+            }
+        };
+
+        let admin = ctx.accounts.admin.key();
+        require_keys_eq!(reward_vault.admin, admin, RewardVaultError::UnauthorizedAdmin);
+        let (expected, _) = Pubkey::find_program_address(&[RewardVault::SEED, admin.as_ref()], &crate::ID);
+        require_keys_eq!(vault_info.key(), expected, RewardVaultError::InvalidAccountLayout);
+
+        // Grow the account, topping up rent from the admin
+        grow_account(
+            &vault_info,
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            RewardVault::LEN,
+        )?;
+
+        reward_vault.version = RewardVault::VERSION;
+        reward_vault.refresh_config_hash();
+        reward_vault.try_serialize(&mut &mut vault_info.try_borrow_mut_data()?[..])?;
+
+        Ok(())
+    }
+
+    /**
+     * Set the vault operator
+     * 
+     * Appoints a key that may change non-destructive configuration such as
+     * cooldowns, the distributor, and the pause flag, but cannot move funds,
+     * change the treasury, or close the vault. None removes the operator.
+     * 
+     * @param ctx - Context containing admin and vault accounts
+     * @param operator - Operator key, or None to remove it
+     */
+    pub fn set_operator(ctx: Context<UpdateVault>, operator: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.reward_vault.operator = operator;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Pause or resume disbursements
+     * 
+     * While paused, every distributor-authorized payout is rejected.
+     * Funding and recipient-initiated claims are unaffected.
+     * 
+     * @param ctx - Context containing admin or operator and vault accounts
+     * @param paused - Whether disbursements are paused
+     */
+    pub fn set_paused(ctx: Context<ConfigureVault>, paused: bool) -> Result<()> {
+        ctx.accounts.reward_vault.paused = paused;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Register a recipient
+     * 
+     * Appends a recipient to the vault's on-chain registry so recurring
+     * payouts can reference it by a compact index. The registry is created
+     * on first use.
+     * 
+     * @param ctx - Context containing admin, vault, and registry accounts
+     * @param recipient - Wallet to register
+     */
+    pub fn register_recipient(ctx: Context<RegisterRecipient>, recipient: Pubkey) -> Result<()> {
+        let registry = &mut ctx.accounts.recipient_registry;
+        registry.vault = ctx.accounts.reward_vault.key();
+        registry.bump = ctx.bumps.recipient_registry;
+
+        require!(
+            registry.recipients.len() < RecipientRegistry::MAX_RECIPIENTS,
+            RewardVaultError::RegistryFull
+        );
+        require!(!registry.recipients.contains(&recipient), RewardVaultError::DuplicateRecipient);
+        registry.recipients.push(recipient);
+
+        Ok(())
+    }
+
+    /**
+     * Disburse to a registered recipient
+     * 
+     * Pays the recipient stored at an index of the vault's registry,
+     * keeping full pubkeys out of instruction data for recurring payouts.
+     * 
+     * @param ctx - Context containing vault, registry, and recipient accounts
+     * @param index - Position of the recipient in the registry
+     * @param amount - Amount to disburse
+     * @param fee_exempt_proof - Proof of the recipient against the fee-exempt root, if exempt
+     */
+    pub fn disburse_to_registered(
+        ctx: Context<DisburseToRegistered>,
+        index: u32,
+        amount: u64,
+        fee_exempt_proof: Option<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        check_disbursement_amount(&ctx.accounts.reward_vault, amount)?;
+        check_memo(&ctx.accounts.reward_vault, None)?;
+
+        // Verify distributor authorization
+        authorize_payout(
+            &ctx.accounts.reward_vault,
+            None,
+            &ctx.accounts.distributor_signer,
+            ctx.accounts.session_key.as_ref(),
+            ctx.accounts.admin_signer.as_ref(),
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        check_disburse_cooldown(&ctx.accounts.reward_vault, now)?;
+        check_recipient_cooldown(&ctx.accounts.reward_vault, &ctx.accounts.recipient_stats, now)?;
+
+        // Resolve the recipient from the registry
+        let recipient = *ctx
+            .accounts
+            .recipient_registry
+            .recipients
+            .get(index as usize)
+            .ok_or(RewardVaultError::InvalidRegistryIndex)?;
+        require_keys_eq!(ctx.accounts.recipient.key(), recipient, RewardVaultError::RecipientMismatch);
+        check_not_blocked(&ctx.accounts.reward_vault, ctx.accounts.blocklist.as_ref(), recipient)?;
+
+        log_compute_units(&ctx.accounts.reward_vault, "payout start");
+        // Route payout based on vault configuration
+        let reward_vault = &ctx.accounts.reward_vault;
+        let fee = protocol_fee(reward_vault, recipient, amount, fee_exempt_proof.as_deref())?;
+        let payout = amount.checked_sub(fee).ok_or(RewardVaultError::ArithmeticOverflow)?;
+        if reward_vault.pay_sol {
+            let balance = reward_vault.to_account_info().lamports();
+            check_payout_balance(reward_vault, balance, amount)?;
+            transfer_vault_lamports(reward_vault, &ctx.accounts.recipient.to_account_info(), payout)?;
+            pay_fee_sol(reward_vault, ctx.accounts.treasury.as_ref(), fee)?;
+        } else {
+            let vault_token = ctx
+                .accounts
+                .vault_token_account
+                .as_ref()
+                .ok_or(RewardVaultError::VaultTokenRequired)?;
+
+            let recipient_token = ctx
+                .accounts
+                .recipient_token_account
+                .as_ref()
+                .ok_or(RewardVaultError::RecipientTokenRequired)?;
+            require_keys_eq!(recipient_token.owner, recipient, RewardVaultError::RecipientMismatch);
+
+            check_payout_balance(reward_vault, vault_token.amount, amount)?;
+            transfer_vault_tokens(reward_vault, vault_token, recipient_token, &ctx.accounts.token_program, payout)?;
+            pay_fee_spl(
+                reward_vault,
+                vault_token,
+                ctx.accounts.treasury_token_account.as_ref(),
+                &ctx.accounts.token_program,
+                fee,
+            )?;
+        }
+        log_compute_units(&ctx.accounts.reward_vault, "payout end");
+
+        record_recipient_payout(
+            &mut ctx.accounts.recipient_stats,
+            ctx.accounts.reward_vault.key(),
+            recipient,
+            payout,
+            ctx.bumps.recipient_stats,
+        )?;
+
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &[(recipient, payout)],
+            fee,
+            now,
+        )?;
+        ctx.accounts.reward_vault.last_disburse_ts = now;
+
+        if ctx.accounts.reward_vault.emit_events {
+            emit!(RewardDisbursed {
+                vault: ctx.accounts.reward_vault.key(),
+                recipient,
+                amount: payout,
+                memo: None,
+                vault_balance_after: vault_balance_after(&ctx.accounts.reward_vault, ctx.accounts.vault_token_account.as_ref().map(AsRef::as_ref))?,
+            });
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Get a scaled amount
+     * 
+     * Splits a raw amount using the vault's decimals, so clients don't each
+     * reimplement the conversion. Returns `(whole, fraction, decimals)`: the
+     * integer part in display units, the remainder `raw - whole *
+     * 10^decimals` in base units, and the decimals that remainder is
+     * expressed in. Delivered to clients as the instruction's return data.
+     * 
+     * @param ctx - Context containing the vault account
+     * @param raw - Amount in base units
+     */
+    pub fn scaled_amount(ctx: Context<GetScaledAmount>, raw: u64) -> Result<(u64, u64, u8)> {
+        scale_amount(&ctx.accounts.reward_vault, raw)
+    }
+
+    /**
+     * Block a recipient
+     * 
+     * Adds an address to the vault's blocklist so no disbursement can pay
+     * it, e.g. for sanctions compliance. The blocklist is created on first
+     * use.
+     * 
+     * @param ctx - Context containing admin or operator, vault, and blocklist accounts
+     * @param recipient - Address to block
+     */
+    pub fn block_recipient(ctx: Context<UpdateBlocklist>, recipient: Pubkey) -> Result<()> {
+        let blocklist = &mut ctx.accounts.blocklist;
+        blocklist.vault = ctx.accounts.reward_vault.key();
+        blocklist.bump = ctx.bumps.blocklist;
+
+        require!(blocklist.entries.len() < Blocklist::MAX_ENTRIES, RewardVaultError::BlocklistFull);
+        if !blocklist.entries.contains(&recipient) {
+            blocklist.entries.push(recipient);
+        }
+
+        ctx.accounts.reward_vault.blocklist_active = true;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Unblock a recipient
+     * 
+     * Removes an address from the vault's blocklist. Disbursements stop
+     * requiring the blocklist once it is empty.
+     * 
+     * @param ctx - Context containing admin or operator, vault, and blocklist accounts
+     * @param recipient - Address to unblock
+     */
+    pub fn unblock_recipient(ctx: Context<UpdateBlocklist>, recipient: Pubkey) -> Result<()> {
+        let blocklist = &mut ctx.accounts.blocklist;
+        blocklist.vault = ctx.accounts.reward_vault.key();
+        blocklist.bump = ctx.bumps.blocklist;
+        blocklist.entries.retain(|entry| *entry != recipient);
+
+        ctx.accounts.reward_vault.blocklist_active = !blocklist.entries.is_empty();
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Set admin co-signing for disbursements
+     * 
+     * For higher-value vaults, requires the admin to co-sign every
+     * distributor-initiated payout alongside the distributor.
+     * 
+     * @param ctx - Context containing admin and vault accounts
+     * @param require_admin_cosign - Whether disbursements need the admin's signature
+     */
+    pub fn set_require_admin_cosign(ctx: Context<UpdateVault>, require_admin_cosign: bool) -> Result<()> {
+        ctx.accounts.reward_vault.require_admin_cosign = require_admin_cosign;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Set the vault's reward tiers
+     * 
+     * When non-empty, every disbursement amount must equal one of the
+     * tiers. An empty list allows any amount.
+     * 
+     * @param ctx - Context containing admin and vault accounts
+     * @param reward_tiers - Allowed disbursement amounts (empty = any amount)
+     */
+    pub fn set_reward_tiers(ctx: Context<UpdateVault>, reward_tiers: Vec<u64>) -> Result<()> {
+        require!(
+            reward_tiers.len() <= RewardVault::MAX_REWARD_TIERS,
+            RewardVaultError::TooManyRewardTiers
+        );
+        require!(reward_tiers.iter().all(|tier| *tier > 0), RewardVaultError::InvalidAmount);
+
+        ctx.accounts.reward_vault.reward_tiers = reward_tiers;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Set an epoch's payout rate
+     * 
+     * For epochs funded in one asset but paid in another, e.g. funded in a
+     * token and paid in SOL. The recorded funded amount is converted at this
+     * fixed-point rate into the epoch's payout budget, which disbursements
+     * against the epoch may not exceed. None removes the conversion.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     * @param payout_rate - Payout units per funded unit, scaled by Epoch::RATE_SCALE
+     */
+    pub fn set_payout_rate(ctx: Context<UpdateEpoch>, payout_rate: Option<u64>) -> Result<()> {
+        require!(payout_rate != Some(0), RewardVaultError::InvalidPayoutRate);
+        ctx.accounts.epoch.payout_rate = payout_rate;
+
+        Ok(())
+    }
+
+    /**
+     * Set an epoch's claim deadline
+     * 
+     * Closes Merkle claims against the epoch after the deadline. The epoch
+     * cannot be swept until the deadline has passed.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     * @param claim_deadline - Last timestamp claims are accepted (0 = no deadline)
+     */
+    pub fn set_claim_deadline(ctx: Context<UpdateEpoch>, claim_deadline: i64) -> Result<()> {
+        require!(claim_deadline >= 0, RewardVaultError::InvalidClaimDeadline);
+        ctx.accounts.epoch.claim_deadline = claim_deadline;
+
+        Ok(())
+    }
+
+    /**
+     * Claim an allocation through a relayer
+     * 
+     * Lets a relayer submit a recipient's Merkle claim and pay its fees, so
+     * players need no SOL for gas. The relayer is reimbursed `relayer_fee`
+     * out of the claimed portion, capped by the vault's max relayer fee, and
+     * the recipient receives the rest. Claims may be partial, as in `claim`.
+     * 
+     * @param ctx - Context containing relayer, recipient, vault, epoch, and claim accounts
+     * @param amount - Allocated amount in the epoch claim tree
+     * @param claim_amount - Portion of the allocation to pay now
+     * @param proof - Merkle proof of `(epoch_index, recipient, amount)` against the claim root
+     * @param relayer_fee - Amount reimbursed to the relayer from the allocation
+     */
+    pub fn claim_relayed(
+        ctx: Context<ClaimRelayed>,
+        amount: u64,
+        claim_amount: u64,
+        proof: Vec<[u8; 32]>,
+        relayer_fee: u64,
+    ) -> Result<()> {
+        require!(amount > 0, RewardVaultError::InvalidAmount);
+        require!(
+            relayer_fee <= ctx.accounts.reward_vault.max_relayer_fee && relayer_fee <= claim_amount,
+            RewardVaultError::RelayerFeeTooHigh
+        );
+
+        // Verify the allocation against the epoch claim tree
+        let recipient = ctx.accounts.recipient.key();
+        check_claim(&ctx.accounts.epoch, recipient, amount, &proof)?;
+        check_terms_acknowledged(&ctx.accounts.epoch, recipient, ctx.accounts.acknowledgment.as_ref())?;
+        check_not_blocked(&ctx.accounts.reward_vault, ctx.accounts.blocklist.as_ref(), recipient)?;
+
+        // Only the unclaimed part of the allocation can be paid
+        let claimed = check_claim_amount(&ctx.accounts.claim, amount, claim_amount)?;
+        let payout = claim_amount
+            .checked_sub(relayer_fee)
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+
+        // Route payout and fee based on vault configuration
+        let reward_vault = &ctx.accounts.reward_vault;
+        if reward_vault.pay_sol {
+            transfer_vault_lamports(reward_vault, &ctx.accounts.recipient.to_account_info(), payout)?;
+            if relayer_fee > 0 {
+                transfer_vault_lamports(reward_vault, &ctx.accounts.relayer.to_account_info(), relayer_fee)?;
+            }
+        } else {
+            pay_out_tokens(
+                reward_vault,
+                ctx.accounts.vault_token_account.as_ref(),
+                ctx.accounts.recipient_token_account.as_ref(),
+                recipient,
+                &ctx.accounts.token_program,
+                payout,
+            )?;
+
+            if relayer_fee > 0 {
+                pay_out_tokens(
+                    reward_vault,
+                    ctx.accounts.vault_token_account.as_ref(),
+                    ctx.accounts.relayer_token_account.as_ref(),
+                    ctx.accounts.relayer.key(),
+                    &ctx.accounts.token_program,
+                    relayer_fee,
+                )?;
+            }
+        }
+
+        record_epoch_disbursement(Some(&mut ctx.accounts.epoch), claim_amount)?;
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &[(recipient, payout)],
+            relayer_fee,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        let claim = &mut ctx.accounts.claim;
+        claim.epoch = ctx.accounts.epoch.key();
+        claim.recipient = recipient;
+        claim.amount = amount;
+        claim.claimed = claimed;
+        claim.bump = ctx.bumps.claim;
+
+        Ok(())
+    }
+
+    /**
+     * Set the maximum relayer fee
+     * 
+     * Caps what a relayer may take from a claim in claim_relayed. Zero
+     * allows relaying only without reimbursement.
+     * 
+     * @param ctx - Context containing admin and vault accounts
+     * @param max_relayer_fee - Largest fee a relayer may deduct per claim
+     */
+    pub fn set_max_relayer_fee(ctx: Context<UpdateVault>, max_relayer_fee: u64) -> Result<()> {
+        ctx.accounts.reward_vault.max_relayer_fee = max_relayer_fee;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Create recipient token accounts in bulk
+     * 
+     * Creates reward-mint ATAs for many recipients ahead of a large SPL
+     * round so later claims are cheaper. Recipients and their ATAs are
+     * supplied as remaining account pairs `(wallet, ata)`; ATAs that already
+     * exist are skipped. Rent is paid by the funder.
+     * 
+     * @param ctx - Context containing funder, vault, and mint accounts
+     */
+    pub fn create_recipient_atas<'info>(ctx: Context<'_, '_, 'info, 'info, CreateRecipientAtas<'info>>) -> Result<()> {
+        let accounts = ctx.remaining_accounts;
+        let pairs = accounts.len() / 2;
+        require!(
+            pairs > 0 && accounts.len() == pairs * 2,
+            RewardVaultError::BatchLengthMismatch
+        );
+        require!(pairs <= RewardVault::MAX_ATA_BATCH, RewardVaultError::BatchTooLarge);
+
+        let mint = ctx.accounts.mint.key();
+        for pair in accounts.chunks(2) {
+            let (wallet, ata) = (&pair[0], &pair[1]);
+            require_keys_eq!(
+                ata.key(),
+                get_associated_token_address(&wallet.key(), &mint),
+                RewardVaultError::InvalidRecipient
+            );
+
+            // Already created; nothing to do
+            if !ata.data_is_empty() {
+                continue;
+            }
+
+            let cpi_accounts = associated_token::Create {
+                payer: ctx.accounts.funder.to_account_info(),
+                associated_token: ata.clone(),
+                authority: wallet.clone(),
+                mint: ctx.accounts.mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            };
+            associated_token::create_idempotent(CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                cpi_accounts,
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Migrate the reward mint
+     * 
+     * Rotates an SPL vault to a new reward token, e.g. after a token
+     * migration. The current vault token account must already be drained or
+     * converted; the new mint is validated and its decimals recorded.
+     * 
+     * @param ctx - Context containing admin, vault, vault token, and new mint accounts
+     * @param new_mint - SPL token mint to pay rewards in from now on
+     */
+    pub fn migrate_reward_mint(ctx: Context<ReconfigurePayout>, new_mint: Pubkey) -> Result<()> {
+        require!(!ctx.accounts.reward_vault.pay_sol, RewardVaultError::NotSplVault);
+
+        // The balance in the current mint must be gone before rotating
+        ensure_vault_empty(&ctx.accounts.reward_vault, ctx.accounts.vault_token_account.as_ref())?;
+
+        let mint_decimals = resolve_mint_decimals(false, Some(new_mint), ctx.accounts.mint.as_ref())?;
+
+        let reward_vault = &mut ctx.accounts.reward_vault;
+        reward_vault.reward_mint = Some(new_mint);
+        reward_vault.mint_decimals = mint_decimals;
+        reward_vault.refresh_config_hash();
+
+        emit!(PayoutReconfigured {
+            vault: reward_vault.key(),
+            pay_sol: false,
+            reward_mint: Some(new_mint),
+        });
+
+        Ok(())
+    }
+
+    /**
+     * Initialize the audit log
+     * 
+     * Creates the vault's audit log ring buffer. From then on every
+     * disbursement must supply it and is recorded in it.
+     * 
+     * @param ctx - Context containing admin, vault, and audit log accounts
+     */
+    pub fn init_audit_log(ctx: Context<InitAuditLog>) -> Result<()> {
+        let audit_log = &mut ctx.accounts.audit_log;
+        audit_log.vault = ctx.accounts.reward_vault.key();
+        audit_log.bump = ctx.bumps.audit_log;
+
+        ctx.accounts.reward_vault.audit_log_active = true;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Get recent disbursements
+     * 
+     * Read-only view returning the audit log's entries, newest first. The
+     * entries are delivered to clients as the instruction's return data.
+     * 
+     * @param ctx - Context containing the audit log account
+     */
+    pub fn get_recent(ctx: Context<GetAuditLog>) -> Result<Vec<LogEntry>> {
+        Ok(ctx.accounts.audit_log.recent())
+    }
+
+    /**
+     * Initialize vault statistics
+     * 
+     * Creates the vault's aggregate statistics account. From then on epoch
+     * creation, funding, and every payout path must supply it and update
+     * its counters.
+     * 
+     * @param ctx - Context containing admin, vault, and stats accounts
+     */
+    pub fn init_vault_stats(ctx: Context<InitVaultStats>) -> Result<()> {
+        let vault_stats = &mut ctx.accounts.vault_stats;
+        vault_stats.vault = ctx.accounts.reward_vault.key();
+        vault_stats.bump = ctx.bumps.vault_stats;
+
+        ctx.accounts.reward_vault.stats_active = true;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Get vault statistics
+     * 
+     * Read-only view returning the vault's aggregate counters, delivered
+     * to clients as the instruction's return data.
+     * 
+     * @param ctx - Context containing the stats account
+     */
+    pub fn get_vault_stats(ctx: Context<GetVaultStats>) -> Result<VaultStatsSummary> {
+        let vault_stats = &ctx.accounts.vault_stats;
+
+        Ok(VaultStatsSummary {
+            total_epochs_created: vault_stats.total_epochs_created,
+            total_funded_all_time: vault_stats.total_funded_all_time,
+            total_disbursed_all_time: vault_stats.total_disbursed_all_time,
+            disbursement_count: vault_stats.disbursement_count,
+        })
+    }
+
+    /**
+     * Create a claim shard for an epoch
+     * 
+     * Very large claim rounds can be split across sub-epochs, each with its
+     * own Merkle root, so concurrent claims write to different accounts
+     * instead of all contending on the epoch. Shards are created in order
+     * starting at 0. Sub-epoch payouts reach the epoch's `total_disbursed`
+     * lazily through `sync_sub_epoch`, and sweeps and rollovers settle any
+     * unsynced shards first.
+     * 
+     * @param ctx - Context containing admin, vault, epoch, and the new sub-epoch
+     * @param shard - Shard number; must equal the epoch's current sub-epoch count
+     * @param claim_root - Merkle root of the shard's claim allocations
+     */
+    pub fn init_sub_epoch(ctx: Context<InitSubEpoch>, shard: u16, claim_root: [u8; 32]) -> Result<()> {
+        require!(claim_root != [0u8; 32], RewardVaultError::InvalidClaimProof);
+
+        let epoch = &mut ctx.accounts.epoch;
+        require!(epoch.status != EpochStatus::Swept && !epoch.rolled_over, RewardVaultError::EpochAlreadySwept);
+        require!(shard == epoch.sub_epoch_count, RewardVaultError::InvalidSubEpoch);
+        require!(shard < SubEpoch::MAX_SHARDS, RewardVaultError::TooManySubEpochs);
+        epoch.sub_epoch_count = shard + 1;
+
+        let sub_epoch = &mut ctx.accounts.sub_epoch;
+        sub_epoch.epoch = epoch.key();
+        sub_epoch.shard = shard;
+        sub_epoch.claim_root = claim_root;
+        sub_epoch.total_disbursed = 0;
+        sub_epoch.synced_disbursed = 0;
+        sub_epoch.bump = ctx.bumps.sub_epoch;
+
+        Ok(())
+    }
+
+    /**
+     * Claim a Merkle allocation from a sub-epoch
+     * 
+     * Same as `claim`, but verified against the shard's claim root and
+     * recorded on the sub-epoch, so the epoch account is only read. The
+     * claim PDA is per epoch, recipient, and shard, so a recipient with
+     * allocations in several shards claims each of them up to its own
+     * amount, in one or more partial claims. Epochs with a payout rate check the claim against
+     * their budget, counting what the epoch has synced plus this shard's
+     * unsynced payouts.
+     * 
+     * @param ctx - Context containing recipient, vault, epoch, sub-epoch, claim, and token accounts
+     * @param amount - Allocated amount in lamports or token units
+     * @param claim_amount - Portion of the allocation to pay now
+     * @param proof - Merkle proof of `(epoch_index, recipient, amount)` against the shard root
+     */
+    pub fn claim_sub_epoch(
+        ctx: Context<ClaimSubEpoch>,
+        amount: u64,
+        claim_amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(amount > 0, RewardVaultError::InvalidAmount);
+
+        // Verify the allocation against the shard claim tree
+        let recipient = ctx.accounts.recipient.key();
+        check_claim_root(&ctx.accounts.epoch, ctx.accounts.sub_epoch.claim_root, recipient, amount, &proof)?;
+        check_terms_acknowledged(&ctx.accounts.epoch, recipient, ctx.accounts.acknowledgment.as_ref())?;
+        check_not_blocked(&ctx.accounts.reward_vault, ctx.accounts.blocklist.as_ref(), recipient)?;
+
+        // Only the unclaimed part of the allocation can be paid
+        let claimed = check_claim_amount(&ctx.accounts.claim, amount, claim_amount)?;
+        check_sub_epoch_budget(&ctx.accounts.epoch, &ctx.accounts.sub_epoch, claim_amount)?;
+
+        // Route payout based on vault configuration
+        let reward_vault = &ctx.accounts.reward_vault;
+        if reward_vault.pay_sol {
+            transfer_vault_lamports(reward_vault, &ctx.accounts.recipient.to_account_info(), claim_amount)?;
+        } else {
+            pay_out_tokens(
+                reward_vault,
+                ctx.accounts.vault_token_account.as_ref(),
+                ctx.accounts.recipient_token_account.as_ref(),
+                recipient,
+                &ctx.accounts.token_program,
+                claim_amount,
+            )?;
+        }
+
+        let sub_epoch = &mut ctx.accounts.sub_epoch;
+        sub_epoch.total_disbursed = sub_epoch
+            .total_disbursed
+            .checked_add(u128::from(claim_amount))
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &[(recipient, claim_amount)],
+            0,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        let claim = &mut ctx.accounts.claim;
+        claim.epoch = ctx.accounts.epoch.key();
+        claim.recipient = recipient;
+        claim.amount = amount;
+        claim.claimed = claimed;
+        claim.bump = ctx.bumps.claim;
+
+        Ok(())
+    }
+
+    /**
+     * Fold a sub-epoch's payouts into its epoch
+     * 
+     * Adds whatever the shard has paid since its last sync to the epoch's
+     * `total_disbursed`. Permissionless, since it only moves bookkeeping
+     * forward.
+     * 
+     * @param ctx - Context containing the epoch and sub-epoch accounts
+     */
+    pub fn sync_sub_epoch(ctx: Context<SyncSubEpoch>) -> Result<()> {
+        let epoch = &mut ctx.accounts.epoch;
+        require!(epoch.status != EpochStatus::Swept && !epoch.rolled_over, RewardVaultError::EpochAlreadySwept);
+
+        let sub_epoch = &mut ctx.accounts.sub_epoch;
+        let pending = sub_epoch.pending_disbursed();
+        epoch.total_disbursed = epoch
+            .total_disbursed
+            .checked_add(pending)
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        sub_epoch.synced_disbursed = sub_epoch.total_disbursed;
+
+        Ok(())
+    }
+
+    /**
+     * Register a session key for the distributor
+     * 
+     * Lets the distributor delegate disbursement signing to a short-lived
+     * key so the main distributor key can stay out of hot infrastructure.
+     * Until `expires_at`, payouts signed by the session key are accepted
+     * when its `SessionKey` account is supplied. A session is tied to the
+     * distributor that registered it and stops working if that key is
+     * rotated out.
+     * 
+     * @param ctx - Context containing distributor, vault, and the new session key account
+     * @param session - Public key allowed to sign in place of the distributor
+     * @param expires_at - Last timestamp the session key is accepted
+     */
+    pub fn register_session_key(ctx: Context<RegisterSessionKey>, session: Pubkey, expires_at: i64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let max_expiry = now
+            .checked_add(SessionKey::MAX_DURATION)
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        require!(expires_at > now && expires_at <= max_expiry, RewardVaultError::InvalidSessionExpiry);
+
+        let session_key = &mut ctx.accounts.session_key;
+        session_key.vault = ctx.accounts.reward_vault.key();
+        session_key.distributor = ctx.accounts.distributor.key();
+        session_key.session = session;
+        session_key.expires_at = expires_at;
+        session_key.bump = ctx.bumps.session_key;
+
+        Ok(())
+    }
+
+    /**
+     * Revoke a distributor session key
+     * 
+     * Closes the `SessionKey` account immediately, e.g. when the session
+     * key may be compromised. Disbursements signed by it fail from then on.
+     * Either the distributor that registered the session or the vault admin
+     * may revoke it, and the signer receives the rent.
+     * 
+     * @param ctx - Context containing the revoking authority, vault, and session key account
+     */
+    pub fn revoke_session_key(ctx: Context<RevokeSessionKey>) -> Result<()> {
+        let session_key = &ctx.accounts.session_key;
+
+        emit!(SessionRevoked {
+            vault: ctx.accounts.reward_vault.key(),
+            session: session_key.session,
+            revoked_by: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    /**
+     * Find the next epoch a recipient can claim
+     * 
+     * Read-only view for claim UIs. `remaining_accounts` are `(epoch,
+     * claim PDA)` pairs, as in `claim_many`. Returns the lowest index among
+     * the supplied epochs that has a claim tree, is still inside its claim
+     * window, and where the recipient has not claimed their whole
+     * allocation, or None if none qualifies. Whether the recipient actually has an allocation is only
+     * known off-chain.
+     * 
+     * @param ctx - Context containing the vault
+     * @param recipient - Recipient to look up
+     */
+    pub fn next_claimable<'info>(
+        ctx: Context<'_, '_, 'info, 'info, NextClaimable<'info>>,
+        recipient: Pubkey,
+    ) -> Result<Option<u64>> {
+        let pairs = ctx.remaining_accounts.chunks_exact(2);
+        require!(pairs.remainder().is_empty(), RewardVaultError::BatchLengthMismatch);
+
+        let vault_key = ctx.accounts.reward_vault.key();
+        let now = Clock::get()?.unix_timestamp;
+        let mut next: Option<u64> = None;
+
+        for pair in pairs {
+            let epoch = Account::<Epoch>::try_from(&pair[0])?;
+            require_keys_eq!(epoch.vault, vault_key, RewardVaultError::EpochMismatch);
+
+            // Skip epochs outside their claim window or without a claim tree
+            let claimable = epoch.status != EpochStatus::Swept
+                && !epoch.rolled_over
+                && (epoch.claim_root != [0u8; 32] || epoch.sub_epoch_count > 0)
+                && (epoch.claim_deadline == 0 || now <= epoch.claim_deadline);
+            if !claimable || next.is_some_and(|index| index <= epoch.index) {
+                continue;
+            }
+
+            // A fully paid claim PDA means the recipient already claimed
+            let claim_info = &pair[1];
+            let (expected, _) = Pubkey::find_program_address(
+                &[Claim::SEED, epoch.key().as_ref(), recipient.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(claim_info.key(), expected, RewardVaultError::InvalidClaimAccount);
+            if Claim::try_read(claim_info)?.is_some_and(|claim| claim.remaining() == 0) {
+                continue;
+            }
+
+            next = Some(epoch.index);
+        }
+
+        Ok(next)
+    }
+
+    /**
+     * Set an epoch's Merkle proof depth cap
+     * 
+     * Claims with a proof longer than the cap fail with a clear error
+     * instead of exhausting the compute budget partway through. A balanced
+     * tree of N leaves needs proofs of ceil(log2(N)) hashes, so a cap of
+     * 20 fits about a million recipients and each extra level doubles
+     * that. Every level costs one keccak hash on-chain.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     * @param max_proof_depth - Longest proof accepted, 1 to 32
+     */
+    pub fn set_max_proof_depth(ctx: Context<UpdateEpoch>, max_proof_depth: u8) -> Result<()> {
+        require!(
+            (1..=Epoch::MAX_PROOF_DEPTH_LIMIT).contains(&max_proof_depth),
+            RewardVaultError::InvalidProofDepth
+        );
+        ctx.accounts.epoch.max_proof_depth = max_proof_depth;
+
+        Ok(())
+    }
+
+    /**
+     * Set an epoch's reward multiplier
+     * 
+     * Boosts (or scales down) every `disburse_sol` and `disburse_spl`
+     * payout tied to the epoch: the paid amount is `base * bps / 10000`.
+     * While boosted, each payout must fit in the epoch's remaining budget.
+     * Merkle claims and fixed payouts are not scaled.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     * @param reward_multiplier_bps - Multiplier in basis points (10000 = 1x)
+     */
+    pub fn set_reward_multiplier(ctx: Context<UpdateEpoch>, reward_multiplier_bps: u16) -> Result<()> {
+        require!(reward_multiplier_bps > 0, RewardVaultError::InvalidRewardMultiplier);
+        ctx.accounts.epoch.reward_multiplier_bps = reward_multiplier_bps;
+
+        Ok(())
+    }
+
+    /**
+     * Reassign an epoch to another vault
+     * 
+     * Used during vault migrations. Both vault admins must sign, and only
+     * epochs that have not paid anything out can move, so payouts are never
+     * split across two vaults' books. The epoch is recreated under the new
+     * vault at `new_index`, subject to the new vault's epoch rules, and its
+     * payout budget moves from the old vault's canonical balance to the new
+     * vault's. The old epoch is marked rolled over, so it pays out nothing
+     * further and its funder contributions can no longer be withdrawn.
+     * 
+     * @param ctx - Context containing both admins, both vaults, both epochs, and token accounts for SPL vaults
+     * @param new_vault - Vault taking over the epoch
+     * @param new_index - Index of the epoch under the new vault
+     */
+    pub fn reassign_epoch(ctx: Context<ReassignEpoch>, new_vault: Pubkey, new_index: u64) -> Result<()> {
+        require_keys_eq!(ctx.accounts.new_vault.key(), new_vault, RewardVaultError::InvalidReassignTarget);
+        require_keys_neq!(new_vault, ctx.accounts.reward_vault.key(), RewardVaultError::InvalidReassignTarget);
+
+        // The budget moves as-is, so both vaults must pay out the same asset
+        let reward_vault = &ctx.accounts.reward_vault;
+        require!(
+            reward_vault.pay_sol == ctx.accounts.new_vault.pay_sol
+                && reward_vault.reward_mint == ctx.accounts.new_vault.reward_mint,
+            RewardVaultError::ReassignAssetMismatch
+        );
+
+        let epoch = &ctx.accounts.epoch;
+        require!(epoch.total_disbursed == 0, RewardVaultError::EpochHasDisbursements);
+        require!(epoch.sub_epoch_count == 0, RewardVaultError::EpochHasSubEpochs);
+        require!(epoch.status != EpochStatus::Swept && !epoch.rolled_over, RewardVaultError::EpochAlreadySwept);
+
+        let spec = EpochSpec {
+            start_ts: epoch.start_ts,
+            end_ts: epoch.end_ts,
+            index: new_index,
+            allowlist_root: epoch.allowlist_root,
+            claim_grace_seconds: epoch.claim_grace_seconds,
+            epoch_distributor: epoch.epoch_distributor,
+        };
+        let now = Clock::get()?.unix_timestamp;
+        validate_epoch_spec(&ctx.accounts.new_vault, &spec, now)?;
+
+        let amount = u64::try_from(epoch.payout_budget()?).map_err(|_| RewardVaultError::ArithmeticOverflow)?;
+        let mut moved = (**epoch).clone();
+        moved.vault = new_vault;
+        moved.index = new_index;
+        moved.bump = ctx.bumps.new_epoch;
+        ctx.accounts.new_epoch.set_inner(moved);
+        record_epoch_window(&mut ctx.accounts.new_vault, spec.end_ts);
+
+        // Move the epoch's budget to the new vault
+        if amount > 0 {
+            let reward_vault = &ctx.accounts.reward_vault;
+            if reward_vault.pay_sol {
+                transfer_vault_lamports(reward_vault, &ctx.accounts.new_vault.to_account_info(), amount)?;
+            } else {
+                let reward_mint = reward_vault
+                    .reward_mint
+                    .ok_or(RewardVaultError::RewardMintRequired)?;
+                let vault_token = ctx
+                    .accounts
+                    .vault_token_account
+                    .as_ref()
+                    .ok_or(RewardVaultError::VaultTokenRequired)?;
+                let new_vault_token = ctx
+                    .accounts
+                    .new_vault_token_account
+                    .as_ref()
+                    .ok_or(RewardVaultError::VaultTokenRequired)?;
+                require_keys_eq!(
+                    new_vault_token.key(),
+                    vault_token_address(&ctx.accounts.new_vault, reward_mint),
+                    RewardVaultError::InvalidVaultTokenAccount
+                );
+
+                transfer_vault_tokens(reward_vault, vault_token, new_vault_token, &ctx.accounts.token_program, amount)?;
+            }
+        }
+
+        let epoch = &mut ctx.accounts.epoch;
+        epoch.rolled_over = true;
+
+        emit!(EpochReassigned {
+            vault: ctx.accounts.reward_vault.key(),
+            epoch_index: epoch.index,
+            new_vault,
+            new_epoch_index: new_index,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * Set compute profiling mode
+     * 
+     * When enabled, the disburse and batch payout paths log the remaining
+     * compute units around their transfers, so CU usage can be profiled in
+     * production without a special build. Off by default; the extra logs
+     * cost compute themselves.
+     * 
+     * @param ctx - Context containing admin and vault accounts
+     * @param debug_profiling - Whether to log compute units
+     */
+    pub fn set_debug_profiling(ctx: Context<UpdateVault>, debug_profiling: bool) -> Result<()> {
+        ctx.accounts.reward_vault.debug_profiling = debug_profiling;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Set or clear a claim delegate
+     * 
+     * Lets a recipient name a guardian who may submit their Merkle claims.
+     * Delegated claims still pay the recipient; the delegate only signs and
+     * covers fees and rent. Passing None revokes the delegate.
+     * 
+     * @param ctx - Context containing recipient, vault, and claim delegate accounts
+     * @param delegate - Guardian allowed to claim for the recipient (None = nobody)
+     */
+    pub fn set_claim_delegate(ctx: Context<SetClaimDelegate>, delegate: Option<Pubkey>) -> Result<()> {
+        let claim_delegate = &mut ctx.accounts.claim_delegate;
+        claim_delegate.vault = ctx.accounts.reward_vault.key();
+        claim_delegate.recipient = ctx.accounts.recipient.key();
+        claim_delegate.delegate = delegate;
+        claim_delegate.bump = ctx.bumps.claim_delegate;
+
+        Ok(())
+    }
+
+    /**
+     * Claim a Merkle allocation as a recipient's delegate
+     * 
+     * Same as `claim`, but signed by the delegate the recipient set in
+     * their `ClaimDelegate` account. The payout always goes to the
+     * recipient and the claim PDA is recorded against them. Claims may be
+     * partial.
+     * 
+     * @param ctx - Context containing delegate, recipient, vault, epoch, claim, and token accounts
+     * @param amount - Allocated amount in lamports or token units
+     * @param claim_amount - Portion of the allocation to pay now
+     * @param proof - Merkle proof of `(epoch_index, recipient, amount)` against the claim root
+     */
+    pub fn claim_delegated(
+        ctx: Context<ClaimDelegated>,
+        amount: u64,
+        claim_amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(amount > 0, RewardVaultError::InvalidAmount);
+        require!(
+            ctx.accounts.claim_delegate.delegate == Some(ctx.accounts.delegate.key()),
+            RewardVaultError::UnauthorizedDelegate
+        );
+
+        // Verify the allocation against the epoch claim tree
+        let recipient = ctx.accounts.recipient.key();
+        check_claim(&ctx.accounts.epoch, recipient, amount, &proof)?;
+        check_terms_acknowledged(&ctx.accounts.epoch, recipient, ctx.accounts.acknowledgment.as_ref())?;
+        check_not_blocked(&ctx.accounts.reward_vault, ctx.accounts.blocklist.as_ref(), recipient)?;
+
+        // Only the unclaimed part of the allocation can be paid
+        let claimed = check_claim_amount(&ctx.accounts.claim, amount, claim_amount)?;
+
+        // Route payout based on vault configuration
+        let reward_vault = &ctx.accounts.reward_vault;
+        if reward_vault.pay_sol {
+            transfer_vault_lamports(reward_vault, &ctx.accounts.recipient.to_account_info(), claim_amount)?;
+        } else {
+            pay_out_tokens(
+                reward_vault,
+                ctx.accounts.vault_token_account.as_ref(),
+                ctx.accounts.recipient_token_account.as_ref(),
+                recipient,
+                &ctx.accounts.token_program,
+                claim_amount,
+            )?;
+        }
+
+        record_epoch_disbursement(Some(&mut ctx.accounts.epoch), claim_amount)?;
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &[(recipient, claim_amount)],
+            0,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        let claim = &mut ctx.accounts.claim;
+        claim.epoch = ctx.accounts.epoch.key();
+        claim.recipient = recipient;
+        claim.amount = amount;
+        claim.claimed = claimed;
+        claim.bump = ctx.bumps.claim;
+
+        Ok(())
+    }
+
+    /**
+     * Commit to an epoch's funding amount
+     * 
+     * Stores a hash of the amount the admin intends to fund, so the
+     * reward size can be announced without letting funders front-run it.
+     * Must happen before the epoch starts, and the commitment must then be
+     * revealed before `start_ts` or the epoch cannot disburse.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     * @param commitment - `keccak(amount_le || salt)` of the planned funding
+     */
+    pub fn commit_funding(ctx: Context<UpdateEpoch>, commitment: [u8; 32]) -> Result<()> {
+        require!(commitment != [0u8; 32], RewardVaultError::InvalidFundingCommitment);
+
+        let epoch = &mut ctx.accounts.epoch;
+        require!(Clock::get()?.unix_timestamp < epoch.start_ts, RewardVaultError::EpochAlreadyStarted);
+        epoch.funding_commitment = commitment;
+        epoch.revealed_funding = None;
+
+        Ok(())
+    }
+
+    /**
+     * Reveal a committed funding amount
+     * 
+     * Checks the amount and salt against the stored commitment and records
+     * the amount. Disbursements against the epoch are blocked until this
+     * succeeds, and it is only accepted before `start_ts`.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     * @param amount - Committed funding amount in lamports or token units
+     * @param salt - Salt used when building the commitment
+     */
+    pub fn reveal_funding(ctx: Context<UpdateEpoch>, amount: u64, salt: [u8; 32]) -> Result<()> {
+        let epoch = &mut ctx.accounts.epoch;
+        require!(epoch.funding_commitment != [0u8; 32], RewardVaultError::InvalidFundingCommitment);
+        require!(Clock::get()?.unix_timestamp < epoch.start_ts, RewardVaultError::EpochAlreadyStarted);
+        require!(
+            funding_commitment(amount, &salt) == epoch.funding_commitment,
+            RewardVaultError::InvalidFundingCommitment
+        );
+        epoch.revealed_funding = Some(amount);
+
+        Ok(())
+    }
+
+    /**
+     * Configure the protocol fee
+     * 
+     * Sets the fee charged on `disburse_sol` and `disburse_spl` payouts and
+     * the Merkle root of partner recipients exempt from it. The fee is taken
+     * out of the disbursed amount and sent to the vault treasury. Exempt
+     * recipients are paid in full when the disbursement carries a valid
+     * membership proof.
+     * 
+     * @param ctx - Context containing admin and vault accounts
+     * @param fee_bps - Fee in basis points, at most 1000 (0 = no fee)
+     * @param fee_exempt_root - Merkle root of fee-exempt recipients (zeroed = none)
+     */
+    pub fn set_fee_config(ctx: Context<UpdateVault>, fee_bps: u16, fee_exempt_root: [u8; 32]) -> Result<()> {
+        require!(fee_bps <= RewardVault::MAX_FEE_BPS, RewardVaultError::InvalidFeeBps);
+
+        let reward_vault = &mut ctx.accounts.reward_vault;
+        reward_vault.fee_bps = fee_bps;
+        reward_vault.fee_exempt_root = fee_exempt_root;
+        reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Set the automatic epoch cadence
+     * 
+     * Enables `crank_epoch` for the vault, which creates each next epoch
+     * `epoch_cadence` seconds long. Zero disables cranking.
+     * 
+     * @param ctx - Context containing admin and vault accounts
+     * @param epoch_cadence - Epoch length in seconds (0 = disabled)
+     */
+    pub fn set_epoch_cadence(ctx: Context<UpdateVault>, epoch_cadence: i64) -> Result<()> {
+        require!(epoch_cadence >= 0, RewardVaultError::InvalidEpochWindow);
+        ctx.accounts.reward_vault.epoch_cadence = epoch_cadence;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Advance to the next recurring epoch
+     * 
+     * Permissionless, so a keeper bot can run recurring rewards. Once the
+     * previous epoch has ended, creates the epoch at the next index with
+     * `start_ts = prev.end_ts` and `end_ts = start_ts + epoch_cadence`,
+     * carrying over the previous epoch's allowlist, grace period, and
+     * distributor. The usual epoch checks apply, so a crank that comes
+     * more than a full cadence late fails and the admin must start the
+     * next epoch by hand.
+     * 
+     * @param ctx - Context containing payer, vault, the previous epoch, and the new epoch
+     */
+    pub fn crank_epoch(ctx: Context<CrankEpoch>) -> Result<()> {
+        let cadence = ctx.accounts.reward_vault.epoch_cadence;
+        require!(cadence > 0, RewardVaultError::EpochCadenceNotSet);
+
+        let prev_epoch = &ctx.accounts.prev_epoch;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= prev_epoch.end_ts, RewardVaultError::EpochNotEnded);
+
+        let start_ts = prev_epoch.end_ts;
+        let spec = EpochSpec {
+            start_ts,
+            end_ts: start_ts.checked_add(cadence).ok_or(RewardVaultError::ArithmeticOverflow)?,
+            index: prev_epoch.index.checked_add(1).ok_or(RewardVaultError::ArithmeticOverflow)?,
+            allowlist_root: prev_epoch.allowlist_root,
+            claim_grace_seconds: prev_epoch.claim_grace_seconds,
+            epoch_distributor: prev_epoch.epoch_distributor,
+        };
+        validate_epoch_spec(&ctx.accounts.reward_vault, &spec, now)?;
+
+        let epoch = new_epoch(ctx.accounts.reward_vault.key(), &spec, ctx.bumps.epoch);
+        ctx.accounts.epoch.set_inner(epoch);
+        record_epoch_window(&mut ctx.accounts.reward_vault, spec.end_ts);
+        if let Some(vault_stats) = active_vault_stats(&ctx.accounts.reward_vault, ctx.accounts.vault_stats.as_mut())? {
+            vault_stats.record_epochs(1)?;
+        }
+
+        emit!(NewEpoch {
+            start_ts: spec.start_ts,
+            end_ts: spec.end_ts,
+            epoch_index: spec.index,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * Finalize an epoch's funding
+     * 
+     * Freezes `total_funded` into `finalized_amount` and locks funding, so
+     * later deposits, rollovers in, or contribution withdrawals cannot
+     * change the amount Merkle and proportional distributions were computed
+     * from. Cannot be undone.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     */
+    pub fn finalize_funding(ctx: Context<UpdateEpoch>) -> Result<()> {
+        let epoch = &mut ctx.accounts.epoch;
+        require!(!epoch.funding_locked, RewardVaultError::FundingLocked);
+        require!(epoch.status != EpochStatus::Swept && !epoch.rolled_over, RewardVaultError::EpochAlreadySwept);
+
+        epoch.finalized_amount = epoch.total_funded;
+        epoch.funding_locked = true;
+
+        Ok(())
+    }
+
+    /**
+     * Set the maximum disbursement fraction
+     * 
+     * Caps any single distributor-initiated payout at a share of the vault's
+     * current balance, so a compromised distributor cannot drain the vault
+     * in one transaction. 10000 disables the cap. Merkle and proportional
+     * claims pay fixed allocations and are not capped.
+     * 
+     * @param ctx - Context containing admin and vault accounts
+     * @param max_disburse_bps - Largest payout in basis points of the balance, 1 to 10000
+     */
+    pub fn set_max_disburse_bps(ctx: Context<UpdateVault>, max_disburse_bps: u16) -> Result<()> {
+        require!(
+            (1..=RewardVault::FULL_DISBURSE_BPS).contains(&max_disburse_bps),
+            RewardVaultError::InvalidMaxDisburseBps
+        );
+        ctx.accounts.reward_vault.max_disburse_bps = max_disburse_bps;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Set the terms an epoch's claimants must acknowledge
+     * 
+     * Once set, Merkle claims against the epoch require the recipient's
+     * `AcknowledgeTerms` account for this hash. A zeroed hash removes the
+     * requirement.
+     * 
+     * @param ctx - Context containing admin, vault, and epoch accounts
+     * @param terms_hash - Hash of the terms document (zeroed = none)
+     */
+    pub fn set_terms_hash(ctx: Context<UpdateEpoch>, terms_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.epoch.terms_hash = terms_hash;
+
+        Ok(())
+    }
+
+    /**
+     * Acknowledge a set of terms
+     * 
+     * Records on-chain that the signing recipient accepted the terms
+     * identified by `terms_hash`. One acknowledgment covers every epoch
+     * that uses the same terms.
+     * 
+     * @param ctx - Context containing the recipient and the new acknowledgment account
+     * @param terms_hash - Hash of the terms being acknowledged
+     */
+    pub fn acknowledge(ctx: Context<Acknowledge>, terms_hash: [u8; 32]) -> Result<()> {
+        require!(terms_hash != [0u8; 32], RewardVaultError::TermsNotAcknowledged);
+
+        let acknowledgment = &mut ctx.accounts.acknowledgment;
+        acknowledgment.recipient = ctx.accounts.recipient.key();
+        acknowledgment.terms_hash = terms_hash;
+        acknowledgment.acknowledged_at = Clock::get()?.unix_timestamp;
+        acknowledgment.bump = ctx.bumps.acknowledgment;
+
+        Ok(())
+    }
+
+    /**
+     * Disburse SPL tokens to multiple recipients
+     * 
+     * SPL counterpart of `disburse_sol_batch`. Recipient token accounts are
+     * passed as writable `remaining_accounts` in `(token account, stats PDA
+     * of its owner)` pairs, matched by position with `amounts`. Every
+     * account must hold the reward mint, and the vault token account must
+     * cover the whole batch before any transfer is made. Transfers use
+     * `transfer_checked` against the reward mint, and the protocol fee is
+     * withheld from every recipient.
+     * 
+     * @param ctx - Context containing vault, distributor, mint, and vault token accounts
+     * @param amounts - Base amount in token units for each recipient, scaled by the epoch's reward multiplier
+     * @param proofs - Allowlist proof for each token account owner, in order (empty = epoch has no allowlist)
+     */
+    pub fn disburse_spl_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DisburseSplBatch<'info>>,
+        amounts: Vec<u64>,
+        proofs: Vec<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        // Run the batch-wide checks shared with disburse_sol_batch
+        let now = Clock::get()?.unix_timestamp;
+        let checks = DisbursementChecks {
+            reward_vault: &ctx.accounts.reward_vault,
+            epoch: ctx.accounts.epoch.as_ref(),
+            distributor_signer: &ctx.accounts.distributor_signer,
+            session_key: ctx.accounts.session_key.as_ref(),
+            admin_signer: ctx.accounts.admin_signer.as_ref(),
+            blocklist: ctx.accounts.blocklist.as_ref(),
+            recipient_stats: None,
+        };
+        check_batch_disbursement(&checks, false, ctx.remaining_accounts, amounts.len(), &proofs, now)?;
+        let amounts = scale_batch(&checks, &amounts)?;
+
+        log_compute_units(&ctx.accounts.reward_vault, "batch payout start");
+        let (payouts, fees) = pay_out_spl_batch(&ctx, &checks, &amounts, &proofs, now)?;
+        log_compute_units(&ctx.accounts.reward_vault, "batch payout end");
+        let disbursed = batch_disbursed(&payouts, fees)?;
+        record_epoch_disbursement(ctx.accounts.epoch.as_mut(), disbursed)?;
+
+        after_payout(
+            &ctx.accounts.reward_vault,
+            ctx.accounts.audit_log.as_mut(),
+            ctx.accounts.vault_stats.as_mut(),
+            &payouts,
+            fees,
+            now,
+        )?;
+        ctx.accounts.reward_vault.last_disburse_ts = now;
+
+        if ctx.accounts.reward_vault.emit_events {
+            let vault_balance_after = vault_balance_after(&ctx.accounts.reward_vault, Some(ctx.accounts.vault_token_account.as_ref()))?;
+            for (recipient, amount) in payouts {
+                emit!(RewardDisbursed {
+                    vault: ctx.accounts.reward_vault.key(),
+                    recipient,
+                    amount,
+                    memo: None,
+                    vault_balance_after,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Set memo-required mode
+     * 
+     * When enabled, every distributor payout must carry a memo for
+     * auditability. `disburse_sol` and `disburse_spl` without a memo are
+     * rejected, and payout paths that take no memo cannot be used.
+     * 
+     * @param ctx - Context containing admin and vault accounts
+     * @param require_memo - Whether payouts must carry a memo
+     */
+    pub fn set_require_memo(ctx: Context<UpdateVault>, require_memo: bool) -> Result<()> {
+        ctx.accounts.reward_vault.require_memo = require_memo;
+        ctx.accounts.reward_vault.refresh_config_hash();
+
+        Ok(())
+    }
+
+    /**
+     * Derive vault PDA
+     * 
+     * Read-only helper returning the reward vault address and bump for an
+     * admin, so integrators can cross-check their own derivation. Needs no
+     * accounts and does not require the vault to exist.
+     * 
+     * @param _ctx - Empty context
+     * @param admin - Admin the vault is derived from
+     */
+    pub fn derive_vault_pda(_ctx: Context<DeriveVaultPda>, admin: Pubkey) -> Result<VaultPda> {
+        let (address, bump) =
+            Pubkey::find_program_address(&[RewardVault::SEED, admin.as_ref()], &crate::ID);
+
+        Ok(VaultPda { address, bump })
+    }
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/**
+ * Validate epoch parameters
+ * 
+ * Shared by single and batched epoch creation: checks the window, grace
+ * period, and the vault's epoch cap.
+ */
+fn validate_epoch_spec(reward_vault: &RewardVault, spec: &EpochSpec, now: i64) -> Result<()> {
+    // Validate epoch time window
+    require!(spec.start_ts < spec.end_ts, RewardVaultError::InvalidEpochWindow);
+
+    // An epoch that has already ended could never pay out, which usually
+    // means the timestamps were given in the wrong units or timezone
+    require!(spec.end_ts > now, RewardVaultError::EpochAlreadyEnded);
+    require!(spec.claim_grace_seconds >= 0, RewardVaultError::InvalidGracePeriod);
+
+    // Enforce the vault's epoch cap, which bounds the epoch PDAs it can own
+    let max_epochs = reward_vault.max_epochs;
+    require!(max_epochs == 0 || spec.index < max_epochs, RewardVaultError::MaxEpochsReached);
+
+    // Keep epochs from overlapping when the vault opts in
+    if reward_vault.non_overlapping_epochs {
+        require!(spec.start_ts >= reward_vault.last_epoch_end, RewardVaultError::OverlappingEpoch);
+    }
+
+    Ok(())
+}
+
+/**
+ * Record an epoch window
+ * 
+ * Tracks the latest end timestamp of any epoch the vault has created,
+ * which later epochs must start after when overlap is disallowed.
+ */
+fn record_epoch_window(reward_vault: &mut RewardVault, end_ts: i64) {
+    reward_vault.last_epoch_end = reward_vault.last_epoch_end.max(end_ts);
+}
+
+/**
+ * Build a fresh epoch
+ * 
+ * Epochs are stored Pending, which leaves their status to follow the
+ * start_ts/end_ts window until a manual transition overrides it.
+ */
+fn new_epoch(vault: Pubkey, spec: &EpochSpec, bump: u8) -> Epoch {
+    Epoch {
+        vault,
+        start_ts: spec.start_ts,
+        end_ts: spec.end_ts,
+        index: spec.index,
+        total_funded: 0,
+        total_disbursed: 0,
+        allowlist_root: spec.allowlist_root,
+        claim_grace_seconds: spec.claim_grace_seconds,
+        status: EpochStatus::Pending,
+        rolled_over: false,
+        epoch_distributor: spec.epoch_distributor,
+        funding_cap: 0,
+        funding_cap_reached: false,
+        hard_cap: 0,
+        funding_commitment: [0u8; 32],
+        revealed_funding: None,
+        finalized_amount: 0,
+        funding_locked: false,
+        terms_hash: [0u8; 32],
+        claim_root: [0u8; 32],
+        per_recipient_amount: 0,
+        remaining_slots: 0,
+        payout_rate: None,
+        claim_deadline: 0,
+        sub_epoch_count: 0,
+        max_proof_depth: Epoch::DEFAULT_MAX_PROOF_DEPTH,
+        reward_multiplier_bps: Epoch::MULTIPLIER_SCALE,
+        version: Epoch::VERSION,
+        bump,
+    }
+}
+
+/**
+ * Create an epoch account from remaining accounts
+ * 
+ * Verifies the supplied account is the epoch PDA for the spec's index,
+ * then creates and initializes it with the admin paying rent.
+ */
+fn create_epoch_account<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, StartEpochs<'info>>,
+    epoch_info: &AccountInfo<'info>,
+    spec: &EpochSpec,
+) -> Result<()> {
+    let vault_key = ctx.accounts.reward_vault.key();
+    let index_seed = Epoch::index_seed(spec.index);
+
+    let (expected, bump) = Epoch::find_address(&vault_key, spec.index);
+    require_keys_eq!(epoch_info.key(), expected, RewardVaultError::EpochMismatch);
+
+    // Create the epoch account signed by its PDA seeds
+    let seeds: [&[u8]; 4] = [Epoch::SEED, vault_key.as_ref(), &index_seed, &[bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = CreateAccount {
+        from: ctx.accounts.admin.to_account_info(),
+        to: epoch_info.clone(),
+    };
+    system_program::create_account(
+        CpiContext::new_with_signer(ctx.accounts.system_program.to_account_info(), cpi_accounts, signer),
+        Rent::get()?.minimum_balance(Epoch::LEN),
+        Epoch::LEN as u64,
+        &crate::ID,
+    )?;
+
+    let epoch = new_epoch(vault_key, spec, bump);
+    epoch.try_serialize(&mut &mut epoch_info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+/**
+ * Resolve mint decimals
+ * 
+ * SOL vaults always use 9 decimals. SPL vaults must supply their reward
+ * mint account, which has to be the configured mint and an initialized
+ * SPL token mint, so a mistyped mint is caught before any disbursement.
+ */
+fn resolve_mint_decimals(pay_sol: bool, reward_mint: Option<Pubkey>, mint: Option<&UncheckedAccount>) -> Result<u8> {
+    if pay_sol {
+        return Ok(RewardVault::SOL_DECIMALS);
+    }
+
+    let reward_mint = reward_mint.ok_or(RewardVaultError::RewardMintRequired)?;
+    let mint = mint.ok_or(RewardVaultError::RewardMintRequired)?;
+    require_keys_eq!(mint.key(), reward_mint, RewardVaultError::InvalidRewardMint);
+    require_keys_eq!(*mint.owner, token::ID, RewardVaultError::InvalidRewardMint);
+
+    // Unpacking rejects accounts that are not initialized mints
+    let mint_state = spl_token::state::Mint::unpack(&mint.try_borrow_data()?)
+        .map_err(|_| RewardVaultError::InvalidRewardMint)?;
+
+    Ok(mint_state.decimals)
+}
+
+/**
+ * Resolve the vault statistics account
+ * 
+ * Once the vault's stats account exists, every instruction that updates
+ * counters must supply it. Returns None while stats are not enabled.
+ */
+fn active_vault_stats<'a, 'info>(
+    reward_vault: &Account<'info, RewardVault>,
+    vault_stats: Option<&'a mut Account<'info, VaultStats>>,
+) -> Result<Option<&'a mut Account<'info, VaultStats>>> {
+    if !reward_vault.stats_active {
+        return Ok(None);
+    }
+
+    let vault_stats = vault_stats.ok_or(RewardVaultError::VaultStatsRequired)?;
+    require_keys_eq!(vault_stats.vault, reward_vault.key(), RewardVaultError::VaultStatsRequired);
+
+    Ok(Some(vault_stats))
+}
+
+/**
+ * Record a completed payout
+ * 
+ * Shared bookkeeping after funds leave the vault. Once the vault's audit
+ * log exists it must be supplied, and each `(recipient, amount)` payout
+ * overwrites the oldest entry. The stats counters gain one disbursement
+ * per payout, totalling the payouts plus any `fees` withheld from them.
+ */
+fn after_payout<'info>(
+    reward_vault: &Account<'info, RewardVault>,
+    audit_log: Option<&mut Account<'info, AuditLog>>,
+    vault_stats: Option<&mut Account<'info, VaultStats>>,
+    payouts: &[(Pubkey, u64)],
+    fees: u64,
+    now: i64,
+) -> Result<()> {
+    if reward_vault.audit_log_active {
+        let audit_log = audit_log.ok_or(RewardVaultError::AuditLogRequired)?;
+        require_keys_eq!(audit_log.vault, reward_vault.key(), RewardVaultError::AuditLogRequired);
+        for (recipient, amount) in payouts {
+            audit_log.record(*recipient, *amount, now);
+        }
+    }
+
+    if let Some(vault_stats) = active_vault_stats(reward_vault, vault_stats)? {
+        let total = payouts
+            .iter()
+            .map(|(_, amount)| u128::from(*amount))
+            .sum::<u128>()
+            .checked_add(u128::from(fees))
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        vault_stats.record_disbursements(total, payouts.len() as u64)?;
+    }
+
+    Ok(())
+}
+
+/**
+ * Check admin co-signature
+ * 
+ * Vaults that require it need the admin to sign each disbursement
+ * alongside the distributor.
+ */
+fn check_admin_cosign(reward_vault: &RewardVault, admin_signer: Option<&UncheckedAccount>) -> Result<()> {
+    if !reward_vault.require_admin_cosign {
+        return Ok(());
+    }
+
+    let admin_signer = admin_signer.ok_or(RewardVaultError::MissingAdminCosignature)?;
+    require_keys_eq!(admin_signer.key(), reward_vault.admin, RewardVaultError::MissingAdminCosignature);
+    require!(admin_signer.is_signer, RewardVaultError::MissingAdminCosignature);
+
+    Ok(())
+}
+
+/**
+ * Check recipient is not blocked
+ * 
+ * Once the vault's blocklist has entries, disbursements must supply it
+ * and the recipient must not be on it.
+ */
+fn check_not_blocked(
+    reward_vault: &Account<RewardVault>,
+    blocklist: Option<&Account<Blocklist>>,
+    recipient: Pubkey,
+) -> Result<()> {
+    if !reward_vault.blocklist_active {
+        return Ok(());
+    }
+
+    let blocklist = blocklist.ok_or(RewardVaultError::BlocklistRequired)?;
+    require_keys_eq!(blocklist.vault, reward_vault.key(), RewardVaultError::BlocklistRequired);
+    require!(!blocklist.entries.contains(&recipient), RewardVaultError::RecipientBlocked);
+
+    Ok(())
+}
+
+/**
+ * Check recipient owner
+ * 
+ * Wallet recipients must be system-owned. When an expected owner program
+ * is given, the recipient must instead be owned by that program, which
+ * may not be this program itself.
+ */
+fn check_recipient_owner(recipient: &AccountInfo, recipient_owner: Option<Pubkey>) -> Result<()> {
+    match recipient_owner {
+        Some(owner) => {
+            require_keys_neq!(owner, crate::ID, RewardVaultError::InvalidRecipient);
+            require_keys_eq!(*recipient.owner, owner, RewardVaultError::InvalidRecipient);
+        }
+        None => {
+            require_keys_eq!(*recipient.owner, System::id(), RewardVaultError::InvalidRecipient);
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * Authorize distributor
+ * 
+ * Shared by every disbursement path: the vault must not be paused, and
+ * the supplied account must be the expected distributor and must have
+ * signed the transaction.
+ */
+fn authorize_distributor(
+    reward_vault: &Account<RewardVault>,
+    epoch: Option<&Account<Epoch>>,
+    distributor: &AccountInfo,
+    session_key: Option<&Account<SessionKey>>,
+) -> Result<()> {
+    require!(!reward_vault.paused, RewardVaultError::VaultPaused);
+
+    let expected = expected_distributor(reward_vault, epoch, session_key)?;
+    require_keys_eq!(distributor.key(), expected, RewardVaultError::UnauthorizedDistributor);
+    require!(distributor.is_signer, RewardVaultError::MissingDistributorSignature);
+
+    Ok(())
+}
+
+/**
+ * Resolve the key a payout must be signed by
+ * 
+ * Payouts tied to an epoch with its own distributor require that key
+ * instead of the vault-level one. A live session key registered by the
+ * expected distributor may sign in its place.
+ */
+fn expected_distributor(
+    reward_vault: &Account<RewardVault>,
+    epoch: Option<&Account<Epoch>>,
+    session_key: Option<&Account<SessionKey>>,
+) -> Result<Pubkey> {
+    let mut expected = reward_vault.distributor;
+    if let Some(epoch) = epoch {
+        require_keys_eq!(epoch.vault, reward_vault.key(), RewardVaultError::EpochMismatch);
+        expected = epoch.epoch_distributor.unwrap_or(expected);
+    }
+
+    // A session key stands in for the distributor that registered it
+    if let Some(session_key) = session_key {
+        require_keys_eq!(session_key.vault, reward_vault.key(), RewardVaultError::InvalidSessionKey);
+        require_keys_eq!(session_key.distributor, expected, RewardVaultError::UnauthorizedDistributor);
+        require!(
+            Clock::get()?.unix_timestamp <= session_key.expires_at,
+            RewardVaultError::SessionExpired
+        );
+        expected = session_key.session;
+    }
+
+    Ok(expected)
+}
+
+/**
+ * Authorize a payout
+ * 
+ * Every distributor-initiated payout goes through here: the distributor
+ * checks of authorize_distributor, plus the admin's co-signature on vaults
+ * that require it.
+ */
+fn authorize_payout(
+    reward_vault: &Account<RewardVault>,
+    epoch: Option<&Account<Epoch>>,
+    distributor: &AccountInfo,
+    session_key: Option<&Account<SessionKey>>,
+    admin_signer: Option<&UncheckedAccount>,
+) -> Result<()> {
+    authorize_distributor(reward_vault, epoch, distributor, session_key)?;
+    check_admin_cosign(reward_vault, admin_signer)
+}
+
+/**
+ * Authorize a voucher
+ * 
+ * The off-chain counterpart of authorize_payout: the same pause,
+ * distributor, session key, and co-signature rules, except the
+ * distributor signs the voucher instead of the transaction. Returns the
+ * key the voucher signature must verify against.
+ */
+fn authorize_voucher(
+    reward_vault: &Account<RewardVault>,
+    epoch: Option<&Account<Epoch>>,
+    session_key: Option<&Account<SessionKey>>,
+    admin_signer: Option<&UncheckedAccount>,
+) -> Result<Pubkey> {
+    require!(!reward_vault.paused, RewardVaultError::VaultPaused);
+    check_admin_cosign(reward_vault, admin_signer)?;
+    expected_distributor(reward_vault, epoch, session_key)
+}
+
+/**
+ * Disbursement Checks
+ * 
+ * Accounts consulted by check_disbursement, gathered so disburse_sol,
+ * disburse_spl, and validate_disbursement share one set of checks.
+ */
+struct DisbursementChecks<'a, 'info> {
+    reward_vault: &'a Account<'info, RewardVault>,       // Vault paying out
+    epoch: Option<&'a Account<'info, Epoch>>,            // Epoch the payout counts against
+    distributor_signer: &'a AccountInfo<'info>,          // Distributor or session key signer
+    session_key: Option<&'a Account<'info, SessionKey>>, // Session standing in for the distributor
+    admin_signer: Option<&'a UncheckedAccount<'info>>,   // Admin co-signer, when required
+    blocklist: Option<&'a Account<'info, Blocklist>>,    // Vault blocklist, once active
+    recipient_stats: Option<&'a Account<'info, RecipientStats>>, // Recipient history (None = never paid)
+}
+
+/**
+ * Check a single-recipient disbursement
+ * 
+ * Every check disburse_sol and disburse_spl make before moving funds:
+ * amount, memo, payout mode, authorization and co-signature, cooldowns,
+ * epoch state, allowlist, and blocklist. Returns the amount after the
+ * epoch's reward multiplier.
+ */
+fn check_disbursement(
+    checks: &DisbursementChecks,
+    pay_sol: bool,
+    recipient: Pubkey,
+    amount: u64,
+    proof: &[[u8; 32]],
+    memo: Option<&str>,
+    now: i64,
+) -> Result<u64> {
+    let reward_vault = checks.reward_vault;
+    check_disbursement_amount(reward_vault, amount)?;
+    check_memo(reward_vault, memo)?;
+    require!(reward_vault.pay_sol == pay_sol, RewardVaultError::WrongPayoutMode);
+
+    // Verify distributor authorization
+    authorize_payout(
+        reward_vault,
+        checks.epoch,
+        checks.distributor_signer,
+        checks.session_key,
+        checks.admin_signer,
+    )?;
+
+    // Enforce the distributor and recipient cooldowns
+    check_disburse_cooldown(reward_vault, now)?;
+    if let Some(recipient_stats) = checks.recipient_stats {
+        check_recipient_cooldown(reward_vault, recipient_stats, now)?;
+    }
+    check_epoch_active(checks.epoch)?;
+    check_epoch_funded(reward_vault, checks.epoch)?;
+    let amount = apply_reward_multiplier(checks.epoch, amount)?;
+
+    // Enforce the epoch allowlist and vault blocklist
+    check_recipient_allowed(checks.epoch, reward_vault.key(), recipient, proof)?;
+    check_not_blocked(reward_vault, checks.blocklist, recipient)?;
+
+    Ok(amount)
+}
+
+/**
+ * Check a batch disbursement
+ * 
+ * The checks of check_disbursement that cover a batch as a whole: memo,
+ * payout mode, authorization and co-signature, the distributor cooldown,
+ * and epoch state. `accounts` must hold one `(recipient, recipient stats)`
+ * pair per entry, with no account repeated, and `proofs` either one
+ * allowlist proof per entry or none.
+ */
+fn check_batch_disbursement(
+    checks: &DisbursementChecks,
+    pay_sol: bool,
+    accounts: &[AccountInfo],
+    entries: usize,
+    proofs: &[Vec<[u8; 32]>],
+    now: i64,
+) -> Result<()> {
+    let reward_vault = checks.reward_vault;
+    check_memo(reward_vault, None)?;
+    require!(reward_vault.pay_sol == pay_sol, RewardVaultError::WrongPayoutMode);
+
+    // Verify distributor authorization
+    authorize_payout(
+        reward_vault,
+        checks.epoch,
+        checks.distributor_signer,
+        checks.session_key,
+        checks.admin_signer,
+    )?;
+
+    // Enforce the distributor cooldown and epoch state
+    check_disburse_cooldown(reward_vault, now)?;
+    check_epoch_active(checks.epoch)?;
+    check_epoch_funded(reward_vault, checks.epoch)?;
+
+    require!(
+        entries > 0 && accounts.len() == entries * 2 && (proofs.is_empty() || proofs.len() == entries),
+        RewardVaultError::BatchLengthMismatch
+    );
+    require!(entries <= RewardVault::MAX_BATCH, RewardVaultError::BatchTooLarge);
+
+    // Reject repeated recipients or stats accounts before crediting anyone
+    ensure_unique_recipients(accounts)
+}
+
+/**
+ * Check and scale batch amounts
+ * 
+ * Each base amount passes check_disbursement_amount and is then scaled
+ * by the epoch's reward multiplier, as in check_disbursement. A boosted
+ * batch must fit in what the epoch has left as a whole, not just per
+ * recipient.
+ */
+fn scale_batch(checks: &DisbursementChecks, amounts: &[u64]) -> Result<Vec<u64>> {
+    let mut scaled = Vec::with_capacity(amounts.len());
+    let mut total: u128 = 0;
+    for amount in amounts {
+        check_disbursement_amount(checks.reward_vault, *amount)?;
+        let amount = apply_reward_multiplier(checks.epoch, *amount)?;
+        total = total
+            .checked_add(u128::from(amount))
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        scaled.push(amount);
+    }
+
+    if let Some(epoch) = checks.epoch {
+        if epoch.reward_multiplier_bps != Epoch::MULTIPLIER_SCALE {
+            let remaining = epoch.payout_budget()?.saturating_sub(epoch.total_disbursed);
+            require!(total <= remaining, RewardVaultError::MultiplierExceedsBudget);
+        }
+    }
+
+    Ok(scaled)
+}
+
+/**
+ * Check one recipient of a batch disbursement
+ * 
+ * The per-recipient checks of check_disbursement: allowlist, blocklist,
+ * and recipient cooldown. Returns the recipient's stats account, loaded
+ * or created from `stats_info`, for the payout to be recorded on.
+ */
+fn check_batch_recipient<'info>(
+    checks: &DisbursementChecks<'_, 'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    stats_info: &'info AccountInfo<'info>,
+    recipient: Pubkey,
+    proof: &[[u8; 32]],
+    now: i64,
+) -> Result<Account<'info, RecipientStats>> {
+    let reward_vault = checks.reward_vault;
+
+    // Enforce the epoch allowlist and vault blocklist
+    check_recipient_allowed(checks.epoch, reward_vault.key(), recipient, proof)?;
+    check_not_blocked(reward_vault, checks.blocklist, recipient)?;
+
+    let recipient_stats = load_recipient_stats(reward_vault, payer, system_program, stats_info, recipient)?;
+    check_recipient_cooldown(reward_vault, &recipient_stats, now)?;
+
+    Ok(recipient_stats)
+}
+
+/**
+ * Load a recipient stats PDA from remaining accounts
+ * 
+ * Used by batch disbursements, where stats accounts cannot be declared in
+ * the accounts struct. The supplied account must be the canonical stats
+ * PDA for the vault and recipient; `payer` funds it on first use.
+ */
+fn load_recipient_stats<'info>(
+    reward_vault: &Account<'info, RewardVault>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    stats_info: &'info AccountInfo<'info>,
+    recipient: Pubkey,
+) -> Result<Account<'info, RecipientStats>> {
+    let vault = reward_vault.key();
+    let (expected, bump) = Pubkey::find_program_address(
+        &[RecipientStats::SEED, vault.as_ref(), recipient.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(stats_info.key(), expected, RewardVaultError::InvalidRecipientStats);
+
+    if stats_info.owner != &crate::ID || stats_info.data_is_empty() {
+        // Create the stats account signed by its PDA seeds
+        let seeds: [&[u8]; 4] = [RecipientStats::SEED, vault.as_ref(), recipient.as_ref(), &[bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = CreateAccount {
+            from: payer.to_account_info(),
+            to: stats_info.clone(),
+        };
+        system_program::create_account(
+            CpiContext::new_with_signer(system_program.to_account_info(), cpi_accounts, signer),
+            Rent::get()?.minimum_balance(RecipientStats::LEN),
+            RecipientStats::LEN as u64,
+            &crate::ID,
+        )?;
+
+        let recipient_stats = RecipientStats {
+            vault,
+            recipient,
+            lifetime_received: 0,
+            last_received_ts: 0,
+            bump,
+        };
+        recipient_stats.try_serialize(&mut &mut stats_info.try_borrow_mut_data()?[..])?;
+    }
+
+    Account::try_from(stats_info)
+}
+
+/**
+ * Total a batch payout
+ * 
+ * What a batch took out of the vault: every payout plus the fees withheld
+ * from them, as counted against the epoch.
+ */
+fn batch_disbursed(payouts: &[(Pubkey, u64)], fees: u64) -> Result<u64> {
+    let disbursed = payouts
+        .iter()
+        .try_fold(fees, |total, (_, amount)| total.checked_add(*amount))
+        .ok_or(RewardVaultError::ArithmeticOverflow)?;
+
+    Ok(disbursed)
+}
+
+/**
+ * Check the vault can fund a payout
+ * 
+ * The paying account must hold `amount`, SOL payouts must leave the
+ * reserve floor intact, and the payout must stay within the vault's
+ * maximum disbursement fraction of `balance`.
+ */
+fn check_payout_balance(reward_vault: &Account<RewardVault>, balance: u64, amount: u64) -> Result<()> {
+    require!(balance >= amount, RewardVaultError::InsufficientVaultBalance);
+    if reward_vault.pay_sol {
+        check_reserve_floor(reward_vault, u128::from(amount))?;
+    }
+
+    check_disburse_fraction(reward_vault, u128::from(balance), u128::from(amount))
+}
+
+/**
+ * Select the token account an SPL disbursement pays from
+ * 
+ * The epoch's own vault token account when one is supplied, which must be
+ * the epoch's PDA owned by the vault; otherwise the vault's canonical
+ * token account.
+ */
+fn payout_token_account<'a, 'info>(
+    reward_vault: &Account<'info, RewardVault>,
+    epoch: Option<&Account<'info, Epoch>>,
+    vault_token: &'a Account<'info, TokenAccount>,
+    epoch_vault_token: Option<&'a Account<'info, TokenAccount>>,
+) -> Result<&'a Account<'info, TokenAccount>> {
+    let reward_mint = reward_vault
+        .reward_mint
+        .ok_or(RewardVaultError::RewardMintRequired)?;
+
+    match epoch_vault_token {
+        Some(epoch_vault_token) => {
+            let epoch = epoch.ok_or(RewardVaultError::EpochRequired)?;
+            require_keys_eq!(epoch.vault, reward_vault.key(), RewardVaultError::EpochMismatch);
+            require_keys_eq!(
+                epoch_vault_token.key(),
+                Epoch::find_vault_token_address(&epoch.key()).0,
+                RewardVaultError::InvalidVaultTokenAccount
+            );
+            require_keys_eq!(epoch_vault_token.owner, reward_vault.key(), RewardVaultError::InvalidVaultTokenAccount);
+            require_keys_eq!(epoch_vault_token.mint, reward_mint, RewardVaultError::MintMismatch);
+            Ok(epoch_vault_token)
+        }
+        None => {
+            require_keys_eq!(vault_token.key(), vault_token_address(reward_vault, reward_mint), RewardVaultError::InvalidVaultTokenAccount);
+            require_keys_eq!(vault_token.mint, reward_mint, RewardVaultError::MintMismatch);
+            Ok(vault_token)
+        }
+    }
+}
+
+/**
+ * Check disbursement amount
+ * 
+ * Rejects zero amounts outright, anything below the vault's configured
+ * minimum disbursement, and off-tier amounts when reward tiers are set.
+ */
+fn check_disbursement_amount(reward_vault: &RewardVault, amount: u64) -> Result<()> {
+    require!(amount > 0, RewardVaultError::InvalidAmount);
+    require!(amount >= reward_vault.min_disbursement, RewardVaultError::BelowMinimum);
+    require!(
+        reward_vault.reward_tiers.is_empty() || reward_vault.reward_tiers.contains(&amount),
+        RewardVaultError::InvalidRewardTier
+    );
+
+    Ok(())
+}
+
+/**
+ * Check distributor cooldown
+ * 
+ * Rejects a disbursement that comes sooner than the configured cooldown
+ * after the previous one. A zero cooldown never blocks.
+ */
+fn check_disburse_cooldown(reward_vault: &RewardVault, now: i64) -> Result<()> {
+    let ready_at = reward_vault
+        .last_disburse_ts
+        .saturating_add(reward_vault.disburse_cooldown);
+    require!(
+        reward_vault.disburse_cooldown == 0 || now >= ready_at,
+        RewardVaultError::CooldownActive
+    );
+
+    Ok(())
+}
+
+/**
+ * Check recipient cooldown
+ * 
+ * Rejects a payout to a recipient who was last paid less than the vault's
+ * recipient cooldown ago. Recipients never paid before are always allowed.
+ */
+fn check_recipient_cooldown(reward_vault: &RewardVault, stats: &RecipientStats, now: i64) -> Result<()> {
+    let ready_at = stats
+        .last_received_ts
+        .saturating_add(reward_vault.recipient_cooldown);
+    require!(
+        reward_vault.recipient_cooldown == 0 || stats.lifetime_received == 0 || now >= ready_at,
+        RewardVaultError::RecipientCooldownActive
+    );
+
+    Ok(())
+}
+
+/**
+ * Check epoch is active
+ * 
+ * Disbursements tied to an epoch are only allowed while it is Active and,
+ * if the admin committed to a funding amount, once it has been revealed.
+ */
+fn check_epoch_active(epoch: Option<&Account<Epoch>>) -> Result<()> {
+    if let Some(epoch) = epoch {
+        let now = Clock::get()?.unix_timestamp;
+        require!(epoch.effective_status(now) == EpochStatus::Active, RewardVaultError::EpochNotActive);
+        require!(!epoch.rolled_over, RewardVaultError::EpochAlreadyRolledOver);
+        require!(
+            epoch.funding_commitment == [0u8; 32] || epoch.revealed_funding.is_some(),
+            RewardVaultError::FundingNotRevealed
+        );
+    }
+
+    Ok(())
+}
+
+/**
+ * Check epoch is funded
+ * 
+ * In strict mode, disbursements must name an epoch that has received
+ * funding, catching vaults funded to the wrong epoch.
+ */
+fn check_epoch_funded(reward_vault: &RewardVault, epoch: Option<&Account<Epoch>>) -> Result<()> {
+    if !reward_vault.require_funded_epoch {
+        return Ok(());
+    }
+
+    let epoch = epoch.ok_or(RewardVaultError::EpochRequired)?;
+    require!(epoch.total_funded > 0, RewardVaultError::EpochNotFunded);
+
+    Ok(())
+}
+
+/**
+ * Read the vault balance after a transfer
+ * 
+ * Lamports held by the vault for SOL vaults, or the token amount of the
+ * given vault token account for SPL vaults. Token balances are read from
+ * the account data, since the deserialized account still holds the amount
+ * loaded before the transfer CPI.
+ */
+fn vault_balance_after(reward_vault: &Account<RewardVault>, vault_token: Option<&AccountInfo>) -> Result<u64> {
+    if reward_vault.pay_sol {
+        return Ok(reward_vault.to_account_info().lamports());
+    }
+
+    let vault_token_info = vault_token.ok_or(RewardVaultError::VaultTokenRequired)?;
+    let balance = token_interface::TokenAccount::try_deserialize(&mut &vault_token_info.try_borrow_data()?[..])?.amount;
+
+    Ok(balance)
+}
+
+/**
+ * Vault initialization parameters
+ * 
+ * Everything init_vault and init_vault_if_needed configure from their
+ * arguments, with metadata already encoded, so both instructions write
+ * and compare the same fields.
+ */
+struct VaultInit {
+    admin: Pubkey,
+    distributor: Pubkey,
+    reward_mint: Option<Pubkey>,
+    pay_sol: bool,
+    min_disbursement: u64,
+    max_epochs: u64,
+    name: [u8; RewardVault::MAX_NAME_LEN],
+    metadata_uri: [u8; RewardVault::MAX_METADATA_URI_LEN],
+}
+
+impl VaultInit {
+    /// Whether an existing vault already holds exactly this configuration.
+    fn matches(&self, reward_vault: &RewardVault) -> bool {
+        reward_vault.admin == self.admin
+            && reward_vault.distributor == self.distributor
+            && reward_vault.reward_mint == self.reward_mint
+            && reward_vault.pay_sol == self.pay_sol
+            && reward_vault.min_disbursement == self.min_disbursement
+            && reward_vault.max_epochs == self.max_epochs
+            && reward_vault.name == self.name
+            && reward_vault.metadata_uri == self.metadata_uri
+    }
+}
+
+/**
+ * Initialize a vault account
+ * 
+ * Shared by init_vault and init_vault_if_needed: writes the requested
+ * configuration plus the defaults every new vault starts with.
+ */
+fn initialize_vault(reward_vault: &mut RewardVault, vault_init: &VaultInit, mint_decimals: u8, bump: u8) {
+    reward_vault.admin = vault_init.admin;
+    reward_vault.distributor = vault_init.distributor;
+    reward_vault.reward_mint = vault_init.reward_mint;
+    reward_vault.pay_sol = vault_init.pay_sol;
+    reward_vault.min_disbursement = vault_init.min_disbursement;
+    reward_vault.funder_allowlist_root = [0u8; 32];
+    reward_vault.max_epochs = vault_init.max_epochs;
+    reward_vault.treasury = vault_init.admin;
+    reward_vault.emit_events = true;
+    reward_vault.max_disburse_bps = RewardVault::FULL_DISBURSE_BPS;
+    reward_vault.mint_decimals = mint_decimals;
+    reward_vault.name = vault_init.name;
+    reward_vault.metadata_uri = vault_init.metadata_uri;
+    reward_vault.version = RewardVault::VERSION;
+    reward_vault.bump = bump;
+    reward_vault.refresh_config_hash();
+}
+
+/**
+ * Scale a raw amount to display units
+ * 
+ * Uses 9 decimals for SOL vaults and the stored mint decimals otherwise.
+ * Returns the whole display units, the fractional remainder in base
+ * units, and the decimals used.
+ */
+fn scale_amount(reward_vault: &RewardVault, raw: u64) -> Result<(u64, u64, u8)> {
+    let decimals = if reward_vault.pay_sol {
+        RewardVault::SOL_DECIMALS
+    } else {
+        reward_vault.mint_decimals
+    };
+
+    let unit = 10u64
+        .checked_pow(u32::from(decimals))
+        .ok_or(RewardVaultError::ArithmeticOverflow)?;
+
+    Ok((raw / unit, raw % unit, decimals))
+}
+
+/**
+ * Encode vault metadata
+ * 
+ * Validates that a metadata value is UTF-8 and fits its field, then
+ * zero-pads it to the field's fixed size.
+ */
+fn encode_metadata<const N: usize>(value: &[u8]) -> Result<[u8; N]> {
+    require!(value.len() <= N, RewardVaultError::MetadataTooLong);
+    require!(std::str::from_utf8(value).is_ok(), RewardVaultError::InvalidMetadata);
+
+    let mut encoded = [0u8; N];
+    encoded[..value.len()].copy_from_slice(value);
+
+    Ok(encoded)
+}
+
+/**
+ * Check disbursement memo
+ * 
+ * Memos are optional unless the vault requires them, and capped in length
+ * to keep events small. Payout paths without a memo parameter pass None,
+ * so they are unavailable on memo-required vaults.
+ */
+fn check_memo(reward_vault: &RewardVault, memo: Option<&str>) -> Result<()> {
+    match memo {
+        Some(memo) => require!(memo.len() <= RewardVault::MAX_MEMO_LEN, RewardVaultError::MemoTooLong),
+        None => require!(!reward_vault.require_memo, RewardVaultError::MemoRequired),
+    }
+
+    Ok(())
+}
+
+/**
+ * Canonical vault token account
+ * 
+ * SPL funds always live in the associated token account of the vault PDA
+ * for the reward mint, so callers cannot redirect tokens elsewhere.
+ */
+fn vault_token_address(reward_vault: &Account<RewardVault>, reward_mint: Pubkey) -> Pubkey {
+    get_associated_token_address(&reward_vault.key(), &reward_mint)
+}
+
+/**
+ * Fund vault with SOL
+ * 
+ * Transfers SOL from funder to vault using system program instruction.
+ * This is a low-level function that handles the actual SOL transfer.
+ * Returns the amount received, which for SOL is always the full amount.
+ */
+fn fund_sol(ctx: &Context<FundVault>, amount: u64) -> Result<u64> {
+    let funder = ctx.accounts.funder.to_account_info();
+    let reward_vault_info = ctx.accounts.reward_vault.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+
+    // Create and invoke system transfer instruction
+    let transfer_ix = system_instruction::transfer(funder.key, reward_vault_info.key, amount);
+    invoke(&transfer_ix, &[funder, reward_vault_info.clone(), system_program])?;
+
+    // The vault must end up rent-exempt, or the deposit could be reclaimed
+    let rent_minimum = Rent::get()?.minimum_balance(reward_vault_info.data_len());
+    require!(
+        reward_vault_info.lamports() >= rent_minimum,
+        RewardVaultError::InsufficientInitialFunding
+    );
+
+    Ok(amount)
+}
+
+/**
+ * Fund vault with SPL tokens
+ * 
+ * Transfers SPL or Token-2022 tokens from funder to vault with
+ * `transfer_checked`, which Token-2022 mints with a transfer fee require.
+ * Validates mint addresses and handles token account transfers.
+ * Returns the net amount the vault token account actually received, which
+ * is less than `amount` for tokens that withhold a transfer fee.
+ */
+fn fund_spl(ctx: &Context<FundVault>, amount: u64) -> Result<u64> {
+    let reward_vault = &ctx.accounts.reward_vault;
+    let reward_mint = reward_vault
+        .reward_mint
+        .ok_or(RewardVaultError::RewardMintRequired)?;
+    let mint = ctx.accounts.mint.as_ref().ok_or(RewardVaultError::RewardMintRequired)?;
+
+    let vault_token = ctx
+        .accounts
+        .vault_token_account
+        .as_ref()
+        .ok_or(RewardVaultError::VaultTokenRequired)?;
+
+    let funder_token = ctx
+        .accounts
+        .funder_token_account
+        .as_ref()
+        .ok_or(RewardVaultError::FunderTokenRequired)?;
+
+    // Validate mint addresses match
+    require_keys_eq!(mint.key(), reward_mint, RewardVaultError::MintMismatch);
+    require_keys_eq!(vault_token.mint, reward_mint, RewardVaultError::MintMismatch);
+    require_keys_eq!(funder_token.mint, reward_mint, RewardVaultError::MintMismatch);
+
+    // Token-2022 mints have their own associated token address for the vault
+    let token_program = ctx.accounts.token_program.to_account_info();
+    require_keys_eq!(
+        vault_token.key(),
+        get_associated_token_address_with_program_id(&reward_vault.key(), &reward_mint, token_program.key),
+        RewardVaultError::InvalidVaultTokenAccount
+    );
+
+    // The source must be the funder's own token account
+    require_keys_eq!(funder_token.owner, ctx.accounts.funder.key(), RewardVaultError::FunderTokenOwnerMismatch);
+
+    // Create token transfer instruction
+    let cpi_accounts = token_interface::TransferChecked {
+        from: funder_token.to_account_info(),
+        mint: mint.to_account_info(),
+        to: vault_token.to_account_info(),
+        authority: ctx.accounts.funder.to_account_info(),
+    };
+
+    // Execute token transfer, measuring the vault balance on either side
+    let balance_before = vault_token.amount;
+    token_interface::transfer_checked(CpiContext::new(token_program, cpi_accounts), amount, mint.decimals)?;
+    let balance_after = vault_balance_after(reward_vault, Some(vault_token.as_ref()))?;
+
+    let received = balance_after
+        .checked_sub(balance_before)
+        .ok_or(RewardVaultError::ArithmeticOverflow)?;
+
+    Ok(received)
+}
+
+/**
+ * Fund vault with SOL wrapped into wSOL
+ * 
+ * Transfers lamports from the funder into the vault's canonical wSOL token
+ * account and syncs the token account so its balance reflects them.
+ */
+fn fund_wrapped_sol(ctx: &Context<FundVault>, amount: u64) -> Result<()> {
+    let vault_token = ctx
+        .accounts
+        .vault_token_account
+        .as_ref()
+        .ok_or(RewardVaultError::VaultTokenRequired)?;
+
+    require_keys_eq!(
+        vault_token.key(),
+        vault_token_address(&ctx.accounts.reward_vault, spl_token::native_mint::ID),
+        RewardVaultError::InvalidVaultTokenAccount
+    );
+
+    let funder = ctx.accounts.funder.to_account_info();
+    let vault_token_info = vault_token.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+
+    // Move lamports into the wSOL account, then sync its token balance
+    let transfer_ix = system_instruction::transfer(funder.key, vault_token_info.key, amount);
+    invoke(&transfer_ix, &[funder, vault_token_info.clone(), system_program])?;
+
+    let cpi_accounts = SyncNative {
+        account: vault_token_info,
+    };
+    token::sync_native(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts))?;
+
+    Ok(())
+}
+
+/**
+ * Sweep epochs when closing a vault
+ * 
+ * Marks every supplied epoch as swept and moves their combined unspent
+ * balance to the treasury in a single transfer. Sub-epoch payouts are
+ * settled into their epoch first so they are not swept a second time.
+ */
+fn sweep_epochs_on_close<'info>(ctx: &Context<'_, '_, 'info, 'info, CloseVault<'info>>) -> Result<()> {
+    let reward_vault = &ctx.accounts.reward_vault;
+    let accounts = ctx.remaining_accounts;
+    require!(accounts.len() <= RewardVault::MAX_BATCH, RewardVaultError::BatchTooLarge);
+    let mut total: u64 = 0;
+
+    // Each epoch is followed by its sub-epochs, if it has any
+    let mut cursor = 0;
+    while cursor < accounts.len() {
+        let mut epoch = Account::<Epoch>::try_from(&accounts[cursor])?;
+        require_keys_eq!(epoch.vault, reward_vault.key(), RewardVaultError::EpochMismatch);
+
+        let shards_end = cursor + 1 + usize::from(epoch.sub_epoch_count);
+        let sub_epochs = accounts
+            .get(cursor + 1..shards_end)
+            .ok_or(RewardVaultError::SubEpochsRequired)?;
+        cursor = shards_end;
+        if epoch.status == EpochStatus::Swept || epoch.rolled_over {
+            continue;
+        }
+
+        settle_sub_epochs(&mut epoch, sub_epochs)?;
+        let remaining = epoch.payout_budget()?.saturating_sub(epoch.total_disbursed);
+        let amount = u64::try_from(remaining).map_err(|_| RewardVaultError::ArithmeticOverflow)?;
+        total = total
+            .checked_add(amount)
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+
+        epoch.status = EpochStatus::Swept;
+        epoch.exit(&crate::ID)?;
+
+        emit!(EpochSwept {
+            vault: reward_vault.key(),
+            epoch_index: epoch.index,
+            amount,
+        });
+    }
+
+    if total == 0 {
+        return Ok(());
+    }
+
+    // Route the combined sweep based on vault configuration
+    let treasury = ctx.accounts.treasury.as_ref().ok_or(RewardVaultError::InvalidTreasury)?;
+    require_keys_eq!(treasury.key(), reward_vault.treasury, RewardVaultError::InvalidTreasury);
+
+    if reward_vault.pay_sol {
+        // The rent-exempt minimum and reserve stay put; clear the reserve before closing
+        transfer_vault_lamports(reward_vault, &treasury.to_account_info(), total)
+    } else {
+        let vault_token = ctx
+            .accounts
+            .vault_token_account
+            .as_ref()
+            .ok_or(RewardVaultError::VaultTokenRequired)?;
+
+        let destination_token = ctx
+            .accounts
+            .destination_token_account
+            .as_ref()
+            .ok_or(RewardVaultError::RecipientTokenRequired)?;
+        require_keys_eq!(destination_token.owner, reward_vault.treasury, RewardVaultError::RecipientMismatch);
+
+        transfer_vault_tokens(reward_vault, vault_token, destination_token, &ctx.accounts.token_program, total)
+    }
+}
+
+/**
+ * Grow a program account
+ * 
+ * Used by layout migrations: tops up the account to the rent-exempt
+ * minimum for its new size from the payer, then reallocs with the new
+ * bytes zeroed.
+ */
+fn grow_account<'info>(
+    account_info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    let rent_minimum = Rent::get()?.minimum_balance(new_len);
+    let shortfall = rent_minimum.saturating_sub(account_info.lamports());
+    if shortfall > 0 {
+        let transfer_ix = system_instruction::transfer(payer.key, account_info.key, shortfall);
+        invoke(&transfer_ix, &[payer.clone(), account_info.clone(), system_program.clone()])?;
+    }
+    account_info.realloc(new_len, true)?;
+
+    Ok(())
+}
+
+/**
+ * Record a funder's contribution to an epoch
+ * 
+ * When the funder supplies their contribution PDA, the amount they put
+ * towards the epoch is tracked there so it can be withdrawn before the
+ * epoch starts. The PDA is created on first use with the funder paying.
+ */
+fn record_funder_contribution(ctx: &Context<FundVault>, amount: u64) -> Result<()> {
+    let Some(contribution_info) = ctx.accounts.funder_contribution.as_ref() else {
+        return Ok(());
+    };
+    let epoch = ctx.accounts.epoch.as_ref().ok_or(RewardVaultError::EpochRequired)?;
+
+    let epoch_key = epoch.key();
+    let funder = ctx.accounts.funder.key();
+    let (expected, bump) = Pubkey::find_program_address(
+        &[FunderContribution::SEED, epoch_key.as_ref(), funder.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(contribution_info.key(), expected, RewardVaultError::InvalidContributionAccount);
+
+    let contribution_info = contribution_info.to_account_info();
+    let mut contribution = if contribution_info.data_is_empty() {
+        // Create the contribution account signed by its PDA seeds
+        let seeds: [&[u8]; 4] = [FunderContribution::SEED, epoch_key.as_ref(), funder.as_ref(), &[bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = CreateAccount {
+            from: ctx.accounts.funder.to_account_info(),
+            to: contribution_info.clone(),
+        };
+        system_program::create_account(
+            CpiContext::new_with_signer(ctx.accounts.system_program.to_account_info(), cpi_accounts, signer),
+            Rent::get()?.minimum_balance(FunderContribution::LEN),
+            FunderContribution::LEN as u64,
+            &crate::ID,
+        )?;
+
+        FunderContribution {
+            epoch: epoch_key,
+            funder,
+            amount: 0,
+            bump,
+        }
+    } else {
+        require_keys_eq!(*contribution_info.owner, crate::ID, RewardVaultError::InvalidContributionAccount);
+        FunderContribution::try_deserialize(&mut &contribution_info.try_borrow_data()?[..])?
+    };
+
+    contribution.amount = contribution
+        .amount
+        .checked_add(amount)
+        .ok_or(RewardVaultError::ArithmeticOverflow)?;
+    contribution.try_serialize(&mut &mut contribution_info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+/**
+ * Create a funding receipt
+ * 
+ * Backs fund_vault idempotency keys. Returns false when the receipt PDA
+ * for the vault, funder, and key already exists, meaning the funding is a
+ * retry that must not transfer again.
+ */
+fn create_fund_receipt(ctx: &Context<FundVault>, idempotency_key: [u8; 16], amount: u64) -> Result<bool> {
+    let receipt_info = ctx
+        .accounts
+        .fund_receipt
+        .as_ref()
+        .ok_or(RewardVaultError::FundReceiptRequired)?;
+
+    let vault_key = ctx.accounts.reward_vault.key();
+    let funder = ctx.accounts.funder.key();
+    let (expected, bump) = Pubkey::find_program_address(
+        &[FundReceipt::SEED, vault_key.as_ref(), funder.as_ref(), &idempotency_key],
+        &crate::ID,
+    );
+    require_keys_eq!(receipt_info.key(), expected, RewardVaultError::InvalidFundReceipt);
+
+    let receipt_info = receipt_info.to_account_info();
+    if !receipt_info.data_is_empty() {
+        require_keys_eq!(*receipt_info.owner, crate::ID, RewardVaultError::InvalidFundReceipt);
+        return Ok(false);
+    }
+
+    // Create the receipt account signed by its PDA seeds
+    let seeds: [&[u8]; 5] = [FundReceipt::SEED, vault_key.as_ref(), funder.as_ref(), &idempotency_key, &[bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = CreateAccount {
+        from: ctx.accounts.funder.to_account_info(),
+        to: receipt_info.clone(),
+    };
+    system_program::create_account(
+        CpiContext::new_with_signer(ctx.accounts.system_program.to_account_info(), cpi_accounts, signer),
+        Rent::get()?.minimum_balance(FundReceipt::LEN),
+        FundReceipt::LEN as u64,
+        &crate::ID,
+    )?;
+
+    let receipt = FundReceipt {
+        vault: vault_key,
+        funder,
+        idempotency_key,
+        amount,
+        bump,
+    };
+    receipt.try_serialize(&mut &mut receipt_info.try_borrow_mut_data()?[..])?;
+
+    Ok(true)
+}
+
+/**
+ * Record funding against an epoch
+ * 
+ * Adds received funds to the epoch's total when funding is tied to an
+ * epoch, emitting FundingCapReached the first time the cap is met.
+ * Funding that would push the total past the epoch's hard cap, or into an
+ * epoch whose funding is locked, fails the whole instruction, transfer
+ * included.
+ */
+fn record_epoch_funding(epoch: Option<&mut Account<Epoch>>, vault: Pubkey, amount: u64) -> Result<()> {
+    let Some(epoch) = epoch else {
+        return Ok(());
+    };
+    require_keys_eq!(epoch.vault, vault, RewardVaultError::EpochMismatch);
+    require!(!epoch.funding_locked, RewardVaultError::FundingLocked);
+    require!(!epoch.rolled_over, RewardVaultError::EpochAlreadyRolledOver);
+
+    // Safely add to total funded with overflow protection
+    epoch.total_funded = epoch
+        .total_funded
+        .checked_add(u128::from(amount))
+        .ok_or(RewardVaultError::ArithmeticOverflow)?;
+    require!(
+        epoch.hard_cap == 0 || epoch.total_funded <= epoch.hard_cap,
+        RewardVaultError::FundingCapExceeded
+    );
+
+    // Signal the first time the epoch reaches its funding cap
+    if epoch.funding_cap > 0 && !epoch.funding_cap_reached && epoch.total_funded >= epoch.funding_cap {
+        epoch.funding_cap_reached = true;
+
+        emit!(FundingCapReached {
+            vault,
+            epoch_index: epoch.index,
+            total_funded: epoch.total_funded,
+        });
+    }
+
+    Ok(())
+}
+
+/**
+ * Pay out SOL from vault
+ * 
+ * Directly transfers SOL from vault to recipient by modifying lamports.
+ * This is a low-level operation that bypasses the system program.
+ * `fee` of `amount` goes to the treasury instead of the recipient.
+ */
+fn pay_out_sol(ctx: &Context<DisburseSol>, amount: u64, fee: u64) -> Result<()> {
+    let reward_vault_info = ctx.accounts.reward_vault.to_account_info();
+    let recipient_info = ctx.accounts.recipient.to_account_info();
+
+    // Check vault has sufficient balance
+    check_payout_balance(&ctx.accounts.reward_vault, reward_vault_info.lamports(), amount)?;
+
+    // Transfer lamports directly, splitting off the protocol fee
+    move_lamports(&reward_vault_info, &recipient_info, amount.checked_sub(fee).ok_or(RewardVaultError::ArithmeticOverflow)?)?;
+    pay_fee_sol(&ctx.accounts.reward_vault, ctx.accounts.treasury.as_ref(), fee)
+}
+
+/**
+ * Move lamports between accounts
+ * 
+ * Debits and credits lamports with checked arithmetic so the transfer is
+ * safe on its own, independent of any balance check done by the caller.
+ */
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let from_balance = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(RewardVaultError::ArithmeticOverflow)?;
+    let to_balance = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(RewardVaultError::ArithmeticOverflow)?;
+
+    **from.try_borrow_mut_lamports()? = from_balance;
+    **to.try_borrow_mut_lamports()? = to_balance;
+
+    Ok(())
+}
+
+/**
+ * Pay out SPL tokens from vault
+ * 
+ * Transfers SPL tokens from vault to recipient using token program.
+ * Uses program-derived address for vault authority. `fee` of `amount`
+ * goes to the treasury's token account instead of the recipient.
+ */
+fn pay_out_spl(ctx: &Context<DisburseSpl>, amount: u64, fee: u64) -> Result<()> {
+    let reward_vault = &ctx.accounts.reward_vault;
+    let reward_mint = reward_vault
+        .reward_mint
+        .ok_or(RewardVaultError::RewardMintRequired)?;
+
+    let recipient_token = &ctx.accounts.recipient_token_account;
+
+    // Pay from the epoch's own token account when supplied, else the shared one
+    let vault_token = payout_token_account(
+        reward_vault,
+        ctx.accounts.epoch.as_ref(),
+        &ctx.accounts.vault_token_account,
+        ctx.accounts.epoch_vault_token_account.as_ref(),
+    )?;
+
+    // Validate mint addresses
+    require_keys_eq!(vault_token.mint, reward_mint, RewardVaultError::MintMismatch);
+    require_keys_eq!(recipient_token.mint, reward_mint, RewardVaultError::MintMismatch);
+
+    // Surface frozen accounts clearly instead of an opaque token program error
+    require!(!vault_token.is_frozen(), RewardVaultError::VaultTokenFrozen);
+    require!(!recipient_token.is_frozen(), RewardVaultError::RecipientTokenFrozen);
+
+    // Check vault holds enough tokens before the CPI for a typed error
+    check_payout_balance(reward_vault, vault_token.amount, amount)?;
+
+    let token_program = ctx.accounts.token_program.to_account_info();
+
+    // Create program-derived address for vault authority
+    let seeds: [&[u8]; 3] = [RewardVault::SEED, reward_vault.admin.as_ref(), &[reward_vault.bump]];
+    let signer = &[&seeds[..]];
+
+    // Create token transfer instruction with program authority
+    let cpi_accounts = Transfer {
+        from: vault_token.to_account_info(),
+        to: recipient_token.to_account_info(),
+        authority: reward_vault.to_account_info(),
+    };
+
+    // Execute token transfer with program signature
+    token::transfer(CpiContext::new_with_signer(token_program, cpi_accounts, signer), amount.checked_sub(fee).ok_or(RewardVaultError::ArithmeticOverflow)?)?;
+
+    // Send the protocol fee to the treasury's token account
+    pay_fee_spl(
+        reward_vault,
+        vault_token,
+        ctx.accounts.treasury_token_account.as_ref(),
+        &ctx.accounts.token_program,
+        fee,
+    )
+}
+
+/**
+ * Record a payout on the recipient's stats account
+ * 
+ * Stamps identity fields on first use and adds the payout to the
+ * recipient's lifetime total with overflow protection.
+ */
+fn record_recipient_payout(
+    stats: &mut Account<RecipientStats>,
+    vault: Pubkey,
+    recipient: Pubkey,
+    amount: u64,
+    bump: u8,
+) -> Result<()> {
+    stats.vault = vault;
+    stats.recipient = recipient;
+    stats.bump = bump;
+    stats.lifetime_received = stats
+        .lifetime_received
+        .checked_add(u128::from(amount))
+        .ok_or(RewardVaultError::ArithmeticOverflow)?;
+    stats.last_received_ts = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+/**
+ * Record a disbursement against an epoch
+ * 
+ * Adds the payout to the epoch's disbursed total when the payout is tied
+ * to an epoch, keeping the sweepable remainder accurate. An epoch whose
+ * remainder was rolled over can no longer pay out.
+ */
+fn record_epoch_disbursement(epoch: Option<&mut Account<Epoch>>, amount: u64) -> Result<()> {
+    if let Some(epoch) = epoch {
+        require!(!epoch.rolled_over, RewardVaultError::EpochAlreadyRolledOver);
+        epoch.total_disbursed = epoch
+            .total_disbursed
+            .checked_add(u128::from(amount))
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+
+        // Epochs funded in another asset may only pay out their converted budget
+        if epoch.payout_rate.is_some() {
+            require!(
+                epoch.total_disbursed <= epoch.payout_budget()?,
+                RewardVaultError::EpochBudgetExceeded
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * Check a sub-epoch payout against its epoch
+ * 
+ * Sub-epoch claims leave the epoch account untouched, so the epoch's own
+ * bookkeeping checks are applied here: the epoch must not have been
+ * swept or rolled over, and epochs with a payout rate must have budget
+ * left for the synced total plus the shard's unsynced payouts.
+ */
+fn check_sub_epoch_budget(epoch: &Epoch, sub_epoch: &SubEpoch, amount: u64) -> Result<()> {
+    require!(epoch.status != EpochStatus::Swept, RewardVaultError::EpochAlreadySwept);
+    require!(!epoch.rolled_over, RewardVaultError::EpochAlreadyRolledOver);
+
+    if epoch.payout_rate.is_some() {
+        let disbursed = epoch
+            .total_disbursed
+            .checked_add(sub_epoch.pending_disbursed())
+            .and_then(|total| total.checked_add(u128::from(amount)))
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        require!(disbursed <= epoch.payout_budget()?, RewardVaultError::EpochBudgetExceeded);
+    }
+
+    Ok(())
+}
+
+/**
+ * Apply an epoch's reward multiplier
+ * 
+ * Scales a base disbursement by `reward_multiplier_bps / 10000`, rounding
+ * down. Boosted payouts must still fit in what the epoch has left to pay
+ * out, so a multiplier cannot spend funds the epoch was never given.
+ */
+fn apply_reward_multiplier(epoch: Option<&Account<Epoch>>, base: u64) -> Result<u64> {
+    let epoch = match epoch {
+        Some(epoch) if epoch.reward_multiplier_bps != Epoch::MULTIPLIER_SCALE => epoch,
+        _ => return Ok(base),
+    };
+
+    let scaled = u128::from(base)
+        .checked_mul(u128::from(epoch.reward_multiplier_bps))
+        .ok_or(RewardVaultError::ArithmeticOverflow)?
+        / u128::from(Epoch::MULTIPLIER_SCALE);
+    let remaining = epoch.payout_budget()?.saturating_sub(epoch.total_disbursed);
+    require!(scaled <= remaining, RewardVaultError::MultiplierExceedsBudget);
+
+    let amount = u64::try_from(scaled).map_err(|_| RewardVaultError::ArithmeticOverflow)?;
+    require!(amount > 0, RewardVaultError::InvalidAmount);
+
+    Ok(amount)
+}
+
+/**
+ * Log remaining compute units when profiling
+ * 
+ * No-op unless the vault has debug profiling enabled; otherwise logs the
+ * label followed by the remaining compute units, so CU usage can be read
+ * from production transaction logs.
+ */
+fn log_compute_units(reward_vault: &RewardVault, label: &str) {
+    if reward_vault.debug_profiling {
+        msg!("profile: {}", label);
+        sol_log_compute_units();
+    }
+}
+
+/**
+ * Sweep SOL from vault to treasury
+ * 
+ * Moves an epoch's unspent lamports to the treasury through
+ * transfer_vault_lamports, so the sweep can never dip into the vault's
+ * rent-exempt minimum or configured reserve.
+ */
+fn sweep_sol(ctx: &Context<SweepEpoch>, amount: u64) -> Result<()> {
+    transfer_vault_lamports(&ctx.accounts.reward_vault, &ctx.accounts.treasury.to_account_info(), amount)
+}
+
+/**
+ * Sweep SPL tokens from vault to treasury
+ * 
+ * Transfers an epoch's unspent tokens to a treasury-owned token account
+ * using the vault PDA as authority.
+ */
+fn sweep_spl(ctx: &Context<SweepEpoch>, amount: u64) -> Result<()> {
+    let reward_vault = &ctx.accounts.reward_vault;
+
+    let vault_token = ctx
+        .accounts
+        .vault_token_account
+        .as_ref()
+        .ok_or(RewardVaultError::VaultTokenRequired)?;
+
+    let destination_token = ctx
+        .accounts
+        .destination_token_account
+        .as_ref()
+        .ok_or(RewardVaultError::RecipientTokenRequired)?;
+
+    // Validate destination ownership
+    require_keys_eq!(destination_token.owner, ctx.accounts.treasury.key(), RewardVaultError::RecipientMismatch);
+
+    transfer_vault_tokens(reward_vault, vault_token, destination_token, &ctx.accounts.token_program, amount)?;
+
+    Ok(())
+}
+
+/**
+ * Ensure vault is empty for its current payout mode
+ * 
+ * SOL vaults may hold nothing beyond their rent-exempt minimum. SPL vaults
+ * must supply their token account, which has to be drained.
+ */
+fn ensure_vault_empty(
+    reward_vault: &Account<RewardVault>,
+    vault_token: Option<&Account<TokenAccount>>,
+) -> Result<()> {
+    if reward_vault.pay_sol {
+        let reward_vault_info = reward_vault.to_account_info();
+        let rent_minimum = Rent::get()?.minimum_balance(reward_vault_info.data_len());
+        require!(
+            reward_vault_info.lamports() <= rent_minimum,
+            RewardVaultError::VaultNotEmpty
+        );
+    } else {
+        let reward_mint = reward_vault
+            .reward_mint
+            .ok_or(RewardVaultError::RewardMintRequired)?;
+
+        let vault_token = vault_token.ok_or(RewardVaultError::VaultTokenRequired)?;
+
+        require_keys_eq!(vault_token.mint, reward_mint, RewardVaultError::MintMismatch);
+        require_keys_eq!(vault_token.key(), vault_token_address(reward_vault, reward_mint), RewardVaultError::InvalidVaultTokenAccount);
+        require!(vault_token.amount == 0, RewardVaultError::VaultNotEmpty);
+    }
+
+    Ok(())
+}
+
+/**
+ * Pay out SOL from vault to a batch of recipients
+ * 
+ * Checks the combined total against the vault balance and every recipient
+ * against check_batch_recipient before moving lamports, so a failing batch
+ * leaves no partial payouts. Each payout is added to its recipient's stats.
+ */
+fn pay_out_sol_batch<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, DisburseSolBatch<'info>>,
+    checks: &DisbursementChecks<'_, 'info>,
+    amounts: &[u64],
+    proofs: &[Vec<[u8; 32]>],
+    now: i64,
+) -> Result<(Vec<(Pubkey, u64)>, u64)> {
+    let reward_vault_info = ctx.accounts.reward_vault.to_account_info();
+
+    // Sum the batch in u128 so crafted amounts near u64::MAX cannot wrap
+    let mut total: u128 = 0;
+    for amount in amounts {
+        total = total
+            .checked_add(u128::from(*amount))
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+    }
+
+    // Check vault has sufficient balance for the whole batch
+    let balance = u128::from(reward_vault_info.lamports());
+    require!(balance >= total, RewardVaultError::InsufficientVaultBalance);
+    check_reserve_floor(&ctx.accounts.reward_vault, total)?;
+    check_disburse_fraction(&ctx.accounts.reward_vault, balance, total)?;
+
+    // Check every recipient and load its stats before moving lamports
+    let mut recipient_stats = Vec::with_capacity(amounts.len());
+    for (index, pair) in ctx.remaining_accounts.chunks(2).enumerate() {
+        let recipient_info = &pair[0];
+        require!(recipient_info.is_writable, RewardVaultError::RecipientNotWritable);
+        require_keys_eq!(*recipient_info.owner, System::id(), RewardVaultError::InvalidRecipient);
+
+        let proof = proofs.get(index).map(Vec::as_slice).unwrap_or_default();
+        recipient_stats.push(check_batch_recipient(
+            checks,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            &pair[1],
+            recipient_info.key(),
+            proof,
+            now,
+        )?);
+    }
+
+    // Transfer lamports directly, withholding each recipient's protocol fee
+    let mut payouts = Vec::with_capacity(amounts.len());
+    let mut fees: u64 = 0;
+    for ((pair, amount), stats) in ctx.remaining_accounts.chunks(2).zip(amounts).zip(recipient_stats.iter_mut()) {
+        let recipient_info = &pair[0];
+        let fee = protocol_fee(&ctx.accounts.reward_vault, recipient_info.key(), *amount, None)?;
+        let payout = amount.checked_sub(fee).ok_or(RewardVaultError::ArithmeticOverflow)?;
+        move_lamports(&reward_vault_info, recipient_info, payout)?;
+
+        // Accumulate lifetime earnings for the recipient
+        let bump = stats.bump;
+        record_recipient_payout(stats, ctx.accounts.reward_vault.key(), recipient_info.key(), payout, bump)?;
+        stats.exit(&crate::ID)?;
+
+        payouts.push((recipient_info.key(), payout));
+        fees = fees.checked_add(fee).ok_or(RewardVaultError::ArithmeticOverflow)?;
+    }
+    pay_fee_sol(&ctx.accounts.reward_vault, ctx.accounts.treasury.as_ref(), fees)?;
+
+    Ok((payouts, fees))
+}
+
+/**
+ * Split an amount by weight
+ * 
+ * Each share is `total * weight / sum(weights)`, rounded down, with the
+ * rounding remainder added to the last share so the shares sum to `total`.
+ */
+fn split_by_weight(total: u64, weights: &[u16]) -> Result<Vec<u64>> {
+    let weight_sum: u128 = weights.iter().map(|weight| u128::from(*weight)).sum();
+    require!(weight_sum > 0, RewardVaultError::ZeroTotalWeight);
+
+    let mut shares: Vec<u64> = weights
+        .iter()
+        .map(|weight| (u128::from(total) * u128::from(*weight) / weight_sum) as u64)
+        .collect();
+
+    // Hand the rounding dust to the last recipient
+    let allocated: u128 = shares.iter().map(|share| u128::from(*share)).sum();
+    let dust = u64::try_from(u128::from(total) - allocated).map_err(|_| RewardVaultError::ArithmeticOverflow)?;
+    if let Some(last) = shares.last_mut() {
+        *last = last.checked_add(dust).ok_or(RewardVaultError::ArithmeticOverflow)?;
+    }
+
+    Ok(shares)
+}
+
+/**
+ * Ensure batch recipients are unique
+ * 
+ * Sorts the recipient keys and scans adjacent pairs, rejecting any key that
+ * appears twice so one account cannot be credited more than once per batch.
+ */
+fn ensure_unique_recipients(recipients: &[AccountInfo]) -> Result<()> {
+    let mut keys: Vec<Pubkey> = recipients.iter().map(|info| info.key()).collect();
+    keys.sort_unstable();
+
+    require!(
+        keys.windows(2).all(|pair| pair[0] != pair[1]),
+        RewardVaultError::DuplicateRecipient
+    );
+
+    Ok(())
+}
+
+/**
+ * Check recipient against epoch allowlist
+ *
+ * When an epoch is supplied and carries a non-zero allowlist root, the
+ * recipient must prove membership. A zeroed root leaves the epoch open.
+ */
+fn check_recipient_allowed(
+    epoch: Option<&Account<Epoch>>,
+    vault: Pubkey,
+    recipient: Pubkey,
+    proof: &[[u8; 32]],
+) -> Result<()> {
+    let Some(epoch) = epoch else {
+        return Ok(());
+    };
+    require_keys_eq!(epoch.vault, vault, RewardVaultError::EpochMismatch);
+
+    require!(
+        is_allowlisted(epoch.allowlist_root, recipient, proof),
+        RewardVaultError::RecipientNotAllowed
+    );
+
+    Ok(())
+}
+
+/**
+ * Check allowlist membership
+ * 
+ * A zeroed root means the list is open. Otherwise the key's keccak leaf
+ * must be proven against the root.
+ */
+fn is_allowlisted(root: [u8; 32], key: Pubkey, proof: &[[u8; 32]]) -> bool {
+    if root == [0u8; 32] {
+        return true;
+    }
+
+    let leaf = keccak::hashv(&[key.as_ref()]).0;
+    verify_merkle_proof(proof, root, leaf)
+}
+
+/**
+ * Verify Merkle proof
+ *
+ * Walks the proof from leaf to root using keccak over sorted pairs, so
+ * off-chain trees must hash siblings in ascending byte order.
+ */
+fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).0
+        } else {
+            keccak::hashv(&[sibling, &node]).0
+        }
+    });
+
+    computed == root
+}
+
+/**
+ * Pay out a proportional claim in SOL
+ * 
+ * Moves lamports from the vault to the claiming recipient after checking
+ * the vault balance.
+ */
+fn pay_out_proportional_sol(ctx: &Context<ClaimProportional>, amount: u64) -> Result<()> {
+    let reward_vault_info = ctx.accounts.reward_vault.to_account_info();
+    let recipient_info = ctx.accounts.recipient.to_account_info();
+
+    // Check vault has sufficient balance
+    let balance = reward_vault_info.lamports();
+    require!(balance >= amount, RewardVaultError::InsufficientVaultBalance);
+    check_reserve_floor(&ctx.accounts.reward_vault, u128::from(amount))?;
+
+    // Transfer lamports directly
+    move_lamports(&reward_vault_info, &recipient_info, amount)?;
+
+    Ok(())
+}
+
+/**
+ * Pay out a proportional claim in SPL tokens
+ * 
+ * Transfers tokens from the vault token account to the recipient's token
+ * account through transfer_vault_tokens, so frozen accounts are reported
+ * the same way as every other SPL payout.
+ */
+fn pay_out_proportional_spl(ctx: &Context<ClaimProportional>, amount: u64) -> Result<()> {
+    pay_out_tokens(
+        &ctx.accounts.reward_vault,
+        ctx.accounts.vault_token_account.as_ref(),
+        ctx.accounts.recipient_token_account.as_ref(),
+        ctx.accounts.recipient.key(),
+        &ctx.accounts.token_program,
+        amount,
+    )
+}
+
+/**
+ * Verify an ed25519 signature via instruction introspection
+ * 
+ * Expects the instruction immediately preceding the current one to be a
+ * single-signature ed25519 program instruction whose public key, message,
+ * and signature are all inline and match the expected values.
+ */
+fn verify_ed25519_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+    signature: &[u8; 64],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, RewardVaultError::InvalidVoucherSignature);
+
+    let ed25519_ix = load_instruction_at_checked(usize::from(current_index - 1), instructions_sysvar)?;
+    require_keys_eq!(ed25519_ix.program_id, ed25519_program::ID, RewardVaultError::InvalidVoucherSignature);
+
+    // Header: signature count, padding, then one 14-byte offsets record
+    let data = &ed25519_ix.data;
+    require!(data.len() >= 16 && data[0] == 1, RewardVaultError::InvalidVoucherSignature);
+
+    let read_u16 = |at: usize| usize::from(u16::from_le_bytes([data[at], data[at + 1]]));
+    let signature_offset = read_u16(2);
+    let signature_ix_index = read_u16(4);
+    let public_key_offset = read_u16(6);
+    let public_key_ix_index = read_u16(8);
+    let message_offset = read_u16(10);
+    let message_size = read_u16(12);
+    let message_ix_index = read_u16(14);
+
+    // All referenced data must live inside the ed25519 instruction itself
+    let inline = usize::from(u16::MAX);
+    require!(
+        signature_ix_index == inline && public_key_ix_index == inline && message_ix_index == inline,
+        RewardVaultError::InvalidVoucherSignature
+    );
+
+    let slice = |offset: usize, len: usize| data.get(offset..offset + len);
+    require!(
+        slice(public_key_offset, 32) == Some(signer.as_ref())
+            && slice(signature_offset, 64) == Some(&signature[..])
+            && message_size == message.len()
+            && slice(message_offset, message_size) == Some(message),
+        RewardVaultError::InvalidVoucherSignature
+    );
+
+    Ok(())
+}
+
+/**
+ * Claim leaf
+ * 
+ * Leaves of an epoch claim tree are
+ * `keccak(epoch_index_le (8 bytes) || recipient (32 bytes) || amount_le (8 bytes))`.
+ * Binding the epoch index into the leaf keeps a proof built for one epoch
+ * from verifying against another epoch's root. Off-chain tree builders
+ * hash leaves this way and pair nodes in sorted order (see
+ * `verify_merkle_proof`).
+ */
+fn claim_leaf(epoch_index: u64, recipient: Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[&epoch_index.to_le_bytes(), recipient.as_ref(), &amount.to_le_bytes()]).0
+}
+
+/**
+ * Transfer lamports out of the vault
+ * 
+ * Generic SOL payout used by the claim paths: checks the vault balance and
+ * reserve floor and moves lamports with checked arithmetic.
+ */
+fn transfer_vault_lamports(reward_vault: &Account<RewardVault>, destination: &AccountInfo, amount: u64) -> Result<()> {
+    let reward_vault_info = reward_vault.to_account_info();
+
+    // Check vault has sufficient balance
+    let balance = reward_vault_info.lamports();
+    require!(balance >= amount, RewardVaultError::InsufficientVaultBalance);
+    check_reserve_floor(reward_vault, u128::from(amount))?;
+
+    move_lamports(&reward_vault_info, destination, amount)
+}
+
+/**
+ * Check the SOL reserve floor
+ * 
+ * SOL payouts must leave the vault holding at least its rent-exempt
+ * minimum plus the configured reserve.
+ */
+fn check_reserve_floor(reward_vault: &Account<RewardVault>, amount: u128) -> Result<()> {
+    let reward_vault_info = reward_vault.to_account_info();
+    let rent_minimum = Rent::get()?.minimum_balance(reward_vault_info.data_len());
+    let floor = u128::from(rent_minimum) + u128::from(reward_vault.sol_reserve);
+
+    let balance = u128::from(reward_vault_info.lamports());
+    require!(balance.saturating_sub(amount) >= floor, RewardVaultError::ReserveFloorHit);
+
+    Ok(())
+}
+
+/**
+ * Transfer tokens out of the vault
+ * 
+ * Generic SPL payout used by the claim paths: validates the canonical vault
+ * token account and destination mint, checks the balance, and transfers
+ * with the vault PDA as authority. Destination ownership is checked by the
+ * caller.
+ */
+fn transfer_vault_tokens<'info>(
+    reward_vault: &Account<'info, RewardVault>,
+    vault_token: &Account<'info, TokenAccount>,
+    destination_token: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let reward_mint = reward_vault
+        .reward_mint
+        .ok_or(RewardVaultError::RewardMintRequired)?;
+
+    // Validate mint addresses
+    require_keys_eq!(vault_token.mint, reward_mint, RewardVaultError::MintMismatch);
+    require_keys_eq!(vault_token.key(), vault_token_address(reward_vault, reward_mint), RewardVaultError::InvalidVaultTokenAccount);
+    require_keys_eq!(destination_token.mint, reward_mint, RewardVaultError::MintMismatch);
+
+    // Surface frozen accounts clearly instead of an opaque token program error
+    require!(!vault_token.is_frozen(), RewardVaultError::VaultTokenFrozen);
+    require!(!destination_token.is_frozen(), RewardVaultError::RecipientTokenFrozen);
+
+    // Check vault holds enough tokens before the CPI for a typed error
+    require!(vault_token.amount >= amount, RewardVaultError::InsufficientVaultBalance);
+
+    // Create program-derived address for vault authority
+    let seeds: [&[u8]; 3] = [RewardVault::SEED, reward_vault.admin.as_ref(), &[reward_vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: vault_token.to_account_info(),
+        to: destination_token.to_account_info(),
+        authority: reward_vault.to_account_info(),
+    };
+
+    token::transfer(
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer),
+        amount,
+    )
+}
+
+/**
+ * Pay tokens to a recipient's token account
+ * 
+ * SPL payout shared by the paths with optional token accounts: both
+ * accounts must be supplied, the recipient account must belong to
+ * `owner`, and the transfer goes through transfer_vault_tokens.
+ */
+fn pay_out_tokens<'info>(
+    reward_vault: &Account<'info, RewardVault>,
+    vault_token: Option<&Account<'info, TokenAccount>>,
+    recipient_token: Option<&Account<'info, TokenAccount>>,
+    owner: Pubkey,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let vault_token = vault_token.ok_or(RewardVaultError::VaultTokenRequired)?;
+    let recipient_token = recipient_token.ok_or(RewardVaultError::RecipientTokenRequired)?;
+    require_keys_eq!(recipient_token.owner, owner, RewardVaultError::RecipientMismatch);
+
+    transfer_vault_tokens(reward_vault, vault_token, recipient_token, token_program, amount)
+}
+
+/**
+ * Check a Merkle claim
+ * 
+ * The epoch must still hold its funds and have a claim root, and the
+ * proof must place the `keccak(epoch_index || recipient || amount)` leaf
+ * (see claim_leaf) in that root.
+ */
+fn check_claim(epoch: &Epoch, recipient: Pubkey, amount: u64, proof: &[[u8; 32]]) -> Result<()> {
+    check_claim_root(epoch, epoch.claim_root, recipient, amount, proof)
+}
+
+/**
+ * Check a Merkle claim against a given root
+ * 
+ * Shared by epoch and sub-epoch claims: the epoch's lifecycle, deadline,
+ * and proof depth cap apply either way, only the claim root differs.
+ */
+fn check_claim_root(
+    epoch: &Epoch,
+    claim_root: [u8; 32],
+    recipient: Pubkey,
+    amount: u64,
+    proof: &[[u8; 32]],
+) -> Result<()> {
+    require!(epoch.status != EpochStatus::Swept && !epoch.rolled_over, RewardVaultError::EpochAlreadySwept);
+    require!(
+        epoch.claim_deadline == 0 || Clock::get()?.unix_timestamp <= epoch.claim_deadline,
+        RewardVaultError::ClaimWindowClosed
+    );
+
+    // Reject oversized proofs up front rather than running out of compute
+    require!(proof.len() <= usize::from(epoch.max_proof_depth), RewardVaultError::ProofTooDeep);
+    require!(
+        claim_root != [0u8; 32]
+            && verify_merkle_proof(proof, claim_root, claim_leaf(epoch.index, recipient, amount)),
+        RewardVaultError::InvalidClaimProof
+    );
+
+    Ok(())
+}
+
+/**
+ * Check a partial claim
+ * 
+ * A claim may pay any non-zero part of what is left of the allocation.
+ * Returns the claimed total after this claim, for the caller to record.
+ */
+fn check_claim_amount(claim: &Claim, amount: u64, claim_amount: u64) -> Result<u64> {
+    require!(claim_amount > 0, RewardVaultError::InvalidAmount);
+
+    let unclaimed = amount.saturating_sub(claim.claimed);
+    require!(unclaimed > 0, RewardVaultError::AlreadyClaimed);
+    require!(claim_amount <= unclaimed, RewardVaultError::ClaimExceedsAllocation);
+
+    let claimed = claim
+        .claimed
+        .checked_add(claim_amount)
+        .ok_or(RewardVaultError::ArithmeticOverflow)?;
+
+    Ok(claimed)
+}
+
+/**
+ * Record a batch claim in its claim PDA
+ * 
+ * Used by batch claims, where claim accounts cannot be declared in the
+ * accounts struct. The supplied account must be the canonical claim PDA
+ * for the epoch and recipient. It is created on first use; an existing
+ * claim is claimed up to its full allocation. Returns the amount this
+ * claim pays.
+ */
+fn record_batch_claim<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, ClaimMany<'info>>,
+    claim_info: &AccountInfo<'info>,
+    epoch: &Account<'info, Epoch>,
+    amount: u64,
+) -> Result<u64> {
+    let recipient = ctx.accounts.recipient.key();
+    let epoch_key = epoch.key();
+
+    let (expected, bump) = Pubkey::find_program_address(
+        &[Claim::SEED, epoch_key.as_ref(), recipient.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(claim_info.key(), expected, RewardVaultError::InvalidClaimAccount);
+
+    let mut claim = match Claim::try_read(claim_info)? {
+        Some(claim) => claim,
+        None => {
+            // Create the claim account signed by its PDA seeds
+            let seeds: [&[u8]; 4] = [Claim::SEED, epoch_key.as_ref(), recipient.as_ref(), &[bump]];
+            let signer = &[&seeds[..]];
+            let cpi_accounts = CreateAccount {
+                from: ctx.accounts.recipient.to_account_info(),
+                to: claim_info.clone(),
+            };
+            system_program::create_account(
+                CpiContext::new_with_signer(ctx.accounts.system_program.to_account_info(), cpi_accounts, signer),
+                Rent::get()?.minimum_balance(Claim::LEN),
+                Claim::LEN as u64,
+                &crate::ID,
+            )?;
+
+            Claim {
+                epoch: epoch_key,
+                recipient,
+                amount,
+                claimed: 0,
+                bump,
+            }
+        }
+    };
+
+    // Pay whatever part of the allocation is still unclaimed
+    let unclaimed = amount.saturating_sub(claim.claimed);
+    require!(unclaimed > 0, RewardVaultError::AlreadyClaimed);
+    claim.claimed = check_claim_amount(&claim, amount, unclaimed)?;
+    claim.amount = amount;
+    claim.try_serialize(&mut &mut claim_info.try_borrow_mut_data()?[..])?;
+
+    Ok(unclaimed)
+}
+
+/**
+ * Settle every sub-epoch of an epoch
+ * 
+ * Used before an epoch's remainder is computed. `accounts` must hold each
+ * of the epoch's sub-epochs exactly once; their unsynced payouts are added
+ * to the epoch's `total_disbursed`. The sub-epochs themselves are left as
+ * is, since the epoch is closed to further claims afterwards.
+ */
+fn settle_sub_epochs<'info>(epoch: &mut Account<Epoch>, accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+    require!(
+        accounts.len() == usize::from(epoch.sub_epoch_count),
+        RewardVaultError::SubEpochsRequired
+    );
+
+    let epoch_key = epoch.key();
+    let mut seen = vec![false; accounts.len()];
+    for sub_epoch_info in accounts {
+        let sub_epoch = Account::<SubEpoch>::try_from(sub_epoch_info)?;
+        require_keys_eq!(sub_epoch.epoch, epoch_key, RewardVaultError::InvalidSubEpoch);
+
+        // Shards are numbered densely from 0, so each slot is hit once
+        let slot = seen
+            .get_mut(usize::from(sub_epoch.shard))
+            .ok_or(RewardVaultError::InvalidSubEpoch)?;
+        require!(!*slot, RewardVaultError::SubEpochsRequired);
+        *slot = true;
+
+        epoch.total_disbursed = epoch
+            .total_disbursed
+            .checked_add(sub_epoch.pending_disbursed())
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+    }
+
+    Ok(())
+}
+
+/**
+ * Hash a funding commitment
+ * 
+ * Commitments are `keccak(amount_le || salt)`; clients must build them
+ * the same way.
+ */
+fn funding_commitment(amount: u64, salt: &[u8; 32]) -> [u8; 32] {
+    keccak::hashv(&[&amount.to_le_bytes(), salt]).0
+}
+
+/**
+ * Settle a vault obligation
+ * 
+ * Called when an escrow is finalized or challenged. Saturates so escrows
+ * opened before the counter existed can still be settled.
+ */
+fn settle_obligation(reward_vault: &mut RewardVault) {
+    reward_vault.outstanding_obligations = reward_vault.outstanding_obligations.saturating_sub(1);
+}
+
+/**
+ * Compute the protocol fee on a disbursement
+ * 
+ * `amount * fee_bps / 10000`, rounded down. Recipients proving membership
+ * in the vault's fee-exempt list pay nothing.
+ */
+fn protocol_fee(
+    reward_vault: &RewardVault,
+    recipient: Pubkey,
+    amount: u64,
+    fee_exempt_proof: Option<&[[u8; 32]]>,
+) -> Result<u64> {
+    if reward_vault.fee_bps == 0 {
+        return Ok(0);
+    }
+
+    if let Some(proof) = fee_exempt_proof {
+        require!(
+            reward_vault.fee_exempt_root != [0u8; 32]
+                && is_allowlisted(reward_vault.fee_exempt_root, recipient, proof),
+            RewardVaultError::InvalidFeeExemption
+        );
+        return Ok(0);
+    }
+
+    let fee = u128::from(amount)
+        .checked_mul(u128::from(reward_vault.fee_bps))
+        .ok_or(RewardVaultError::ArithmeticOverflow)?
+        / RewardVault::FEE_BPS_SCALE;
+
+    u64::try_from(fee).map_err(|_| RewardVaultError::ArithmeticOverflow.into())
+}
+
+/**
+ * Send a SOL protocol fee to the treasury
+ * 
+ * Moves `fee` lamports from the vault to its treasury account, which must
+ * be supplied whenever the fee is non-zero.
+ */
+fn pay_fee_sol(reward_vault: &Account<RewardVault>, treasury: Option<&UncheckedAccount>, fee: u64) -> Result<()> {
+    if fee == 0 {
+        return Ok(());
+    }
+
+    let treasury = treasury.ok_or(RewardVaultError::TreasuryRequired)?;
+    require_keys_eq!(treasury.key(), reward_vault.treasury, RewardVaultError::InvalidTreasury);
+
+    move_lamports(&reward_vault.to_account_info(), &treasury.to_account_info(), fee)
+}
+
+/**
+ * Send an SPL protocol fee to the treasury
+ * 
+ * Transfers `fee` tokens from the paying vault token account to a
+ * treasury-owned token account of the reward mint, which must be supplied
+ * whenever the fee is non-zero.
+ */
+fn pay_fee_spl<'info>(
+    reward_vault: &Account<'info, RewardVault>,
+    vault_token: &Account<'info, TokenAccount>,
+    treasury_token: Option<&Account<'info, TokenAccount>>,
+    token_program: &Program<'info, Token>,
+    fee: u64,
+) -> Result<()> {
+    if fee == 0 {
+        return Ok(());
+    }
+
+    let reward_mint = reward_vault
+        .reward_mint
+        .ok_or(RewardVaultError::RewardMintRequired)?;
+    let treasury_token = treasury_token.ok_or(RewardVaultError::TreasuryRequired)?;
+    require_keys_eq!(treasury_token.mint, reward_mint, RewardVaultError::MintMismatch);
+    require_keys_eq!(treasury_token.owner, reward_vault.treasury, RewardVaultError::InvalidTreasury);
+
+    // Create program-derived address for vault authority
+    let seeds: [&[u8]; 3] = [RewardVault::SEED, reward_vault.admin.as_ref(), &[reward_vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: vault_token.to_account_info(),
+        to: treasury_token.to_account_info(),
+        authority: reward_vault.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer),
+        fee,
+    )
+}
+
+/**
+ * Check a payout against the vault's balance fraction cap
+ * 
+ * Limits the damage one malicious distributor-initiated disbursement can
+ * do: `amount` may be at most `balance * max_disburse_bps / 10000`. Batch
+ * payouts are checked as a whole.
+ */
+fn check_disburse_fraction(reward_vault: &RewardVault, balance: u128, amount: u128) -> Result<()> {
+    if reward_vault.max_disburse_bps >= RewardVault::FULL_DISBURSE_BPS {
+        return Ok(());
+    }
+
+    let limit = balance
+        .checked_mul(u128::from(reward_vault.max_disburse_bps))
+        .ok_or(RewardVaultError::ArithmeticOverflow)?
+        / u128::from(RewardVault::FULL_DISBURSE_BPS);
+    require!(amount <= limit, RewardVaultError::ExceedsMaxDisburseFraction);
+
+    Ok(())
+}
+
+/**
+ * Check the recipient acknowledged the epoch's terms
+ * 
+ * Epochs with a terms hash only pay recipients holding an acknowledgment
+ * of exactly those terms. The account can only be created by the
+ * recipient's own `acknowledge`, so matching its stored fields suffices.
+ */
+fn check_terms_acknowledged(
+    epoch: &Epoch,
+    recipient: Pubkey,
+    acknowledgment: Option<&Account<AcknowledgeTerms>>,
+) -> Result<()> {
+    if epoch.terms_hash == [0u8; 32] {
+        return Ok(());
+    }
+
+    let acknowledgment = acknowledgment.ok_or(RewardVaultError::TermsNotAcknowledged)?;
+    require!(
+        acknowledgment.recipient == recipient && acknowledgment.terms_hash == epoch.terms_hash,
+        RewardVaultError::TermsNotAcknowledged
+    );
+
+    Ok(())
+}
+
+/**
+ * Pay out an SPL batch from the vault
+ * 
+ * Sums the batch with overflow protection and checks the vault token
+ * balance covers it before moving anything, then validates each recipient
+ * token account and transfers with `transfer_checked`. Returns the owner
+ * of each recipient token account, in order.
+ */
+fn pay_out_spl_batch<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, DisburseSplBatch<'info>>,
+    checks: &DisbursementChecks<'_, 'info>,
+    amounts: &[u64],
+    proofs: &[Vec<[u8; 32]>],
+    now: i64,
+) -> Result<(Vec<(Pubkey, u64)>, u64)> {
+    let reward_vault = &ctx.accounts.reward_vault;
+    let reward_mint = reward_vault
+        .reward_mint
+        .ok_or(RewardVaultError::RewardMintRequired)?;
+    let vault_token = &ctx.accounts.vault_token_account;
+    let mint = &ctx.accounts.mint;
+
+    // Validate the vault token account
+    require_keys_eq!(vault_token.mint, reward_mint, RewardVaultError::MintMismatch);
+    require_keys_eq!(vault_token.key(), vault_token_address(reward_vault, reward_mint), RewardVaultError::InvalidVaultTokenAccount);
+    require!(!vault_token.is_frozen(), RewardVaultError::VaultTokenFrozen);
+
+    // Sum the batch in u128 so crafted amounts near u64::MAX cannot wrap
+    let mut total: u128 = 0;
+    for amount in amounts {
+        total = total
+            .checked_add(u128::from(*amount))
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+    }
+
+    // Check the vault holds enough tokens for the whole batch
+    let balance = u128::from(vault_token.amount);
+    require!(balance >= total, RewardVaultError::InsufficientVaultBalance);
+    check_disburse_fraction(reward_vault, balance, total)?;
+
+    // Check every token account and load its owner's stats before any transfer
+    let mut recipients = Vec::with_capacity(amounts.len());
+    for (index, pair) in ctx.remaining_accounts.chunks(2).enumerate() {
+        let recipient_info = &pair[0];
+        require!(recipient_info.is_writable, RewardVaultError::RecipientNotWritable);
+        let recipient_token = Account::<TokenAccount>::try_from(recipient_info)?;
+        require_keys_eq!(recipient_token.mint, reward_mint, RewardVaultError::MintMismatch);
+        require!(!recipient_token.is_frozen(), RewardVaultError::RecipientTokenFrozen);
+
+        let proof = proofs.get(index).map(Vec::as_slice).unwrap_or_default();
+        let recipient_stats = check_batch_recipient(
+            checks,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            &pair[1],
+            recipient_token.owner,
+            proof,
+            now,
+        )?;
+        recipients.push((recipient_token.owner, recipient_stats));
+    }
+
+    // Create program-derived address for vault authority
+    let seeds: [&[u8]; 3] = [RewardVault::SEED, reward_vault.admin.as_ref(), &[reward_vault.bump]];
+    let signer = &[&seeds[..]];
+
+    // Withhold each recipient's protocol fee, sent to the treasury in one transfer
+    let mut payouts = Vec::with_capacity(amounts.len());
+    let mut fees: u64 = 0;
+    for ((pair, amount), (recipient, stats)) in ctx.remaining_accounts.chunks(2).zip(amounts).zip(recipients.iter_mut()) {
+        let fee = protocol_fee(reward_vault, *recipient, *amount, None)?;
+        let payout = amount.checked_sub(fee).ok_or(RewardVaultError::ArithmeticOverflow)?;
+
+        let cpi_accounts = TransferChecked {
+            from: vault_token.to_account_info(),
+            mint: mint.to_account_info(),
+            to: pair[0].clone(),
+            authority: reward_vault.to_account_info(),
+        };
+        token::transfer_checked(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer),
+            payout,
+            mint.decimals,
+        )?;
+
+        // Accumulate lifetime earnings for the token account owner
+        let bump = stats.bump;
+        record_recipient_payout(stats, reward_vault.key(), *recipient, payout, bump)?;
+        stats.exit(&crate::ID)?;
+
+        payouts.push((*recipient, payout));
+        fees = fees.checked_add(fee).ok_or(RewardVaultError::ArithmeticOverflow)?;
+    }
+    pay_fee_spl(
+        reward_vault,
+        vault_token,
+        ctx.accounts.treasury_token_account.as_ref(),
+        &ctx.accounts.token_program,
+        fees,
+    )?;
+
+    Ok((payouts, fees))
+}
+
+// ============================================================================
+// ACCOUNT STRUCTURES
+// ============================================================================
+
+/**
+ * Initialize Vault Context
+ * 
+ * Accounts required for vault initialization including admin signer,
+ * vault account creation, the reward mint for SPL vaults, and system
+ * program.
+ */
+#[derive(Accounts)]
+pub struct InitVault<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: unpacked and matched against reward_mint in resolve_mint_decimals
+    pub mint: Option<UncheckedAccount<'info>>,
+    #[account(
+        init,
+        payer = admin,
+        space = RewardVault::LEN,
+        seeds = [RewardVault::SEED, admin.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Initialize Vault If Needed Context
+ * 
+ * Same accounts as InitVault, but the vault account is only created when
+ * it does not already exist.
+ */
+#[derive(Accounts)]
+pub struct InitVaultIfNeeded<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: unpacked and matched against reward_mint in resolve_mint_decimals
+    pub mint: Option<UncheckedAccount<'info>>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = RewardVault::LEN,
+        seeds = [RewardVault::SEED, admin.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Update Vault Context
+ * 
+ * Accounts required for admin-only vault configuration changes
+ * including admin signer and vault account.
+ */
+#[derive(Accounts)]
+pub struct UpdateVault<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+}
+
+/**
+ * Reconfigure Payout Context
+ * 
+ * Accounts required for switching payout mode including admin signer,
+ * vault account, the vault token account when leaving SPL mode, and the
+ * new reward mint when entering SPL mode.
+ */
+#[derive(Accounts)]
+pub struct ReconfigurePayout<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    /// CHECK: unpacked and matched against reward_mint in resolve_mint_decimals
+    pub mint: Option<UncheckedAccount<'info>>,
+}
+
+/**
  * Start Epoch Context
  * 
- * Accounts required for epoch initialization including admin signer,
- * vault account, epoch account creation, and system program.
+ * Accounts required for epoch initialization including admin signer,
+ * vault account, epoch account creation, and system program. Epochs are
+ * addressed by vault and index so several can exist at once; epochs
+ * created under the original `[b"epoch", vault]` seed must go through
+ * migrate_epoch before use.
+ */
+#[derive(Accounts)]
+#[instruction(start_ts: i64, end_ts: i64, index: u64)]
+pub struct StartEpoch<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        init,
+        payer = admin,
+        space = Epoch::LEN,
+        seeds = [Epoch::SEED, reward_vault.key().as_ref(), &Epoch::index_seed(index)],
+        bump
+    )]
+    pub epoch: Account<'info, Epoch>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Update Epoch Context
+ * 
+ * Accounts required for admin-only epoch changes including admin signer,
+ * vault account, and the epoch being updated.
+ */
+#[derive(Accounts)]
+pub struct UpdateEpoch<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch
+    )]
+    pub epoch: Account<'info, Epoch>,
+}
+
+/**
+ * Fund Vault Context
+ * 
+ * Accounts required for vault funding including vault account,
+ * funder signer, optional epoch tracking, and token accounts. Token
+ * accounts may belong to either the SPL Token or the Token-2022 program.
+ */
+#[derive(Accounts)]
+pub struct FundVault<'info> {
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    #[account(mut)]
+    pub epoch: Option<Account<'info, Epoch>>,
+    #[account(mut)]
+    pub funder_token_account: Option<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(mut)]
+    pub vault_token_account: Option<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    pub mint: Option<InterfaceAccount<'info, token_interface::Mint>>,
+    /// CHECK: address derived and initialized in record_funder_contribution
+    #[account(mut)]
+    pub funder_contribution: Option<UncheckedAccount<'info>>,
+    /// CHECK: address derived and initialized in create_fund_receipt
+    #[account(mut)]
+    pub fund_receipt: Option<UncheckedAccount<'info>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Disburse SOL Context
+ * 
+ * Accounts required for SOL disbursement including vault account,
+ * distributor signer, recipient account, recipient stats, and system program.
+ */
+#[derive(Accounts)]
+pub struct DisburseSol<'info> {
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    /// CHECK: verified as signer against stored distributor key
+    pub distributor_signer: AccountInfo<'info>,
+    pub session_key: Option<Account<'info, SessionKey>>,
+    /// CHECK: verified as signer against stored admin key when co-signing is required
+    pub admin_signer: Option<UncheckedAccount<'info>>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(mut)]
+    pub epoch: Option<Account<'info, Epoch>>,
+    /// CHECK: owner validated in check_recipient_owner
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+    /// CHECK: address constrained to the vault's stored treasury
+    #[account(mut, address = reward_vault.treasury @ RewardVaultError::InvalidTreasury)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = RecipientStats::LEN,
+        seeds = [RecipientStats::SEED, reward_vault.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub recipient_stats: Account<'info, RecipientStats>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Disburse SPL Context
+ * 
+ * Accounts required for SPL token disbursement including vault account,
+ * distributor signer, token accounts, recipient stats, and token program.
+ * The optional epoch vault token account replaces the shared one as the
+ * payout source when present.
+ */
+#[derive(Accounts)]
+pub struct DisburseSpl<'info> {
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    /// CHECK: verified as signer against stored distributor key
+    pub distributor_signer: AccountInfo<'info>,
+    pub session_key: Option<Account<'info, SessionKey>>,
+    /// CHECK: verified as signer against stored admin key when co-signing is required
+    pub admin_signer: Option<UncheckedAccount<'info>>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(mut)]
+    pub epoch: Option<Account<'info, Epoch>>,
+    #[account(mut)]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub epoch_vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = RecipientStats::LEN,
+        seeds = [RecipientStats::SEED, reward_vault.key().as_ref(), recipient_token_account.owner.as_ref()],
+        bump
+    )]
+    pub recipient_stats: Account<'info, RecipientStats>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Disburse SOL Batch Context
+ * 
+ * Accounts required for batched SOL disbursement including vault account,
+ * distributor signer, optional epoch, payer for new recipient stats, and
+ * system program. Recipients and their stats PDAs are supplied as writable
+ * remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct DisburseSolBatch<'info> {
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    /// CHECK: verified as signer against stored distributor key
+    pub distributor_signer: AccountInfo<'info>,
+    pub session_key: Option<Account<'info, SessionKey>>,
+    /// CHECK: verified as signer against stored admin key when co-signing is required
+    pub admin_signer: Option<UncheckedAccount<'info>>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(mut)]
+    pub epoch: Option<Account<'info, Epoch>>,
+    /// CHECK: address constrained to the vault's stored treasury
+    #[account(mut, address = reward_vault.treasury @ RewardVaultError::InvalidTreasury)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Init Stake Snapshot Context
+ * 
+ * Accounts required for creating an epoch's stake snapshot including
+ * admin signer, vault account, epoch account, and snapshot account.
+ */
+#[derive(Accounts)]
+pub struct InitStakeSnapshot<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch)]
+    pub epoch: Account<'info, Epoch>,
+    #[account(
+        init,
+        payer = admin,
+        space = StakeSnapshot::LEN,
+        seeds = [StakeSnapshot::SEED, epoch.key().as_ref()],
+        bump
+    )]
+    pub stake_snapshot: Account<'info, StakeSnapshot>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Record Weight Context
+ * 
+ * Accounts required for recording a recipient's stake weight including
+ * admin signer, vault, epoch, snapshot, and the recipient's weight account.
+ */
+#[derive(Accounts)]
+#[instruction(recipient: Pubkey)]
+pub struct RecordWeight<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch)]
+    pub epoch: Account<'info, Epoch>,
+    #[account(
+        mut,
+        seeds = [StakeSnapshot::SEED, epoch.key().as_ref()],
+        bump = stake_snapshot.bump
+    )]
+    pub stake_snapshot: Account<'info, StakeSnapshot>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = Weight::LEN,
+        seeds = [Weight::SEED, epoch.key().as_ref(), recipient.as_ref()],
+        bump
+    )]
+    pub weight: Account<'info, Weight>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Claim Proportional Context
+ * 
+ * Accounts required for a proportional claim including recipient signer,
+ * vault, epoch, snapshot, weight, and token accounts for SPL vaults.
+ */
+#[derive(Accounts)]
+pub struct ClaimProportional<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch
+    )]
+    pub epoch: Account<'info, Epoch>,
+    #[account(
+        mut,
+        seeds = [StakeSnapshot::SEED, epoch.key().as_ref()],
+        bump = stake_snapshot.bump
+    )]
+    pub stake_snapshot: Account<'info, StakeSnapshot>,
+    #[account(
+        mut,
+        seeds = [Weight::SEED, epoch.key().as_ref(), recipient.key().as_ref()],
+        bump = weight.bump
+    )]
+    pub weight: Account<'info, Weight>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Validate Disbursement Context
+ * 
+ * Accounts required for a dry-run disbursement check: the same optional
+ * accounts disburse_sol/disburse_spl read, without the ones only written.
+ */
+#[derive(Accounts)]
+#[instruction(amount: u64, pay_sol: bool, recipient: Pubkey)]
+pub struct ValidateDisbursement<'info> {
+    #[account(
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    /// CHECK: verified as signer against stored distributor key
+    pub distributor_signer: AccountInfo<'info>,
+    pub session_key: Option<Account<'info, SessionKey>>,
+    /// CHECK: verified as signer against stored admin key when co-signing is required
+    pub admin_signer: Option<UncheckedAccount<'info>>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    pub epoch: Option<Account<'info, Epoch>>,
+    #[account(
+        seeds = [RecipientStats::SEED, reward_vault.key().as_ref(), recipient.as_ref()],
+        bump = recipient_stats.bump
+    )]
+    pub recipient_stats: Option<Account<'info, RecipientStats>>,
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    pub epoch_vault_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/**
+ * Sweep Epoch Context
+ * 
+ * Accounts required for sweeping an epoch including admin signer,
+ * vault account, epoch account, treasury, and token accounts for SPL vaults.
+ */
+#[derive(Accounts)]
+pub struct SweepEpoch<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch
+    )]
+    pub epoch: Account<'info, Epoch>,
+    /// CHECK: address constrained to the vault's stored treasury
+    #[account(mut, address = reward_vault.treasury @ RewardVaultError::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub destination_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+/**
+ * Rollover Epoch Context
+ * 
+ * Accounts required for rolling funds between epochs including admin
+ * signer, vault account, and the source and target epochs.
+ */
+#[derive(Accounts)]
+#[instruction(from_index: u64, to_index: u64)]
+pub struct RolloverEpoch<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        seeds = [Epoch::SEED, reward_vault.key().as_ref(), &Epoch::index_seed(from_index)],
+        bump = from_epoch.bump
+    )]
+    pub from_epoch: Account<'info, Epoch>,
+    #[account(
+        mut,
+        seeds = [Epoch::SEED, reward_vault.key().as_ref(), &Epoch::index_seed(to_index)],
+        bump = to_epoch.bump
+    )]
+    pub to_epoch: Account<'info, Epoch>,
+}
+
+/**
+ * Redeem Voucher Context
+ * 
+ * Accounts required for voucher redemption including recipient signer,
+ * vault account, replay-protection receipt, the epoch the voucher is
+ * charged to (optional), the session key and admin co-signer when used,
+ * token and treasury accounts, and the instructions sysvar for signature
+ * introspection.
+ */
+#[derive(Accounts)]
+#[instruction(amount: u64, expiry: i64, nonce: u64)]
+pub struct RedeemVoucher<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        init,
+        payer = recipient,
+        space = VoucherReceipt::LEN,
+        seeds = [VoucherReceipt::SEED, reward_vault.key().as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub voucher_receipt: Account<'info, VoucherReceipt>,
+    #[account(
+        mut,
+        constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch
+    )]
+    pub epoch: Option<Account<'info, Epoch>>,
+    pub session_key: Option<Account<'info, SessionKey>>,
+    /// CHECK: verified as signer against stored admin key when co-signing is required
+    pub admin_signer: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+    /// CHECK: address constrained to the vault's stored treasury
+    #[account(mut, address = reward_vault.treasury @ RewardVaultError::InvalidTreasury)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+    /// CHECK: address constrained to the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Get Epoch Summary Context
+ * 
+ * Accounts required for the read-only epoch summary view.
+ */
+#[derive(Accounts)]
+pub struct GetEpochSummary<'info> {
+    pub epoch: Account<'info, Epoch>,
+}
+
+/**
+ * Claim Reward Context
+ * 
+ * Accounts required for a Merkle claim including recipient signer, vault,
+ * epoch, the claim PDA guarding against double claims, and token
+ * accounts for SPL vaults.
+ */
+#[derive(Accounts)]
+pub struct ClaimReward<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch
+    )]
+    pub epoch: Account<'info, Epoch>,
+    #[account(
+        init_if_needed,
+        payer = recipient,
+        space = Claim::LEN,
+        seeds = [Claim::SEED, epoch.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, Claim>,
+    /// CHECK: matched against the destination argument and checked to be a wallet
+    #[account(mut)]
+    pub destination: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub acknowledgment: Option<Account<'info, AcknowledgeTerms>>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Has Claimed Context
+ * 
+ * Accounts required for the read-only claim status view. The claim
+ * account is address-checked but may not exist yet.
+ */
+#[derive(Accounts)]
+#[instruction(recipient: Pubkey)]
+pub struct HasClaimed<'info> {
+    pub epoch: Account<'info, Epoch>,
+    /// CHECK: address derived from seeds; may be uninitialized
+    #[account(seeds = [Claim::SEED, epoch.key().as_ref(), recipient.as_ref()], bump)]
+    pub claim: UncheckedAccount<'info>,
+}
+
+/**
+ * Claim Many Context
+ * 
+ * Accounts required for a multi-epoch claim including recipient signer,
+ * vault, and token accounts for SPL vaults. Epoch and claim accounts are
+ * supplied as writable remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct ClaimMany<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub acknowledgment: Option<Account<'info, AcknowledgeTerms>>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Cancel Epoch Context
+ * 
+ * Accounts required for cancelling an epoch including admin signer,
+ * vault account, and the epoch being closed.
+ */
+#[derive(Accounts)]
+pub struct CancelEpoch<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        close = admin,
+        constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch
+    )]
+    pub epoch: Account<'info, Epoch>,
+}
+
+/**
+ * Verify Config Context
+ * 
+ * Accounts required for the read-only configuration hash check.
+ */
+#[derive(Accounts)]
+pub struct VerifyConfig<'info> {
+    #[account(
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+}
+
+/**
+ * Init Epoch Vault Context
+ * 
+ * Accounts required for creating a per-epoch vault token account including
+ * admin signer, vault, epoch, reward mint, and the token account PDA.
+ */
+#[derive(Accounts)]
+pub struct InitEpochVault<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch)]
+    pub epoch: Account<'info, Epoch>,
+    #[account(constraint = Some(reward_mint.key()) == reward_vault.reward_mint @ RewardVaultError::MintMismatch)]
+    pub reward_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [Epoch::VAULT_SEED, epoch.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = reward_vault
+    )]
+    pub epoch_vault_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Start Epochs Context
+ * 
+ * Accounts required for batched epoch initialization including admin
+ * signer, vault account, and system program. Epoch PDAs are supplied as
+ * writable remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct StartEpochs<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Disburse Fixed Context
+ * 
+ * Accounts required for a fixed-amount disbursement including vault,
+ * distributor signer, epoch, recipient, recipient stats, and token
+ * accounts for SPL vaults.
+ */
+#[derive(Accounts)]
+pub struct DisburseFixed<'info> {
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    /// CHECK: verified as signer against stored distributor key
+    pub distributor_signer: AccountInfo<'info>,
+    pub session_key: Option<Account<'info, SessionKey>>,
+    /// CHECK: verified as signer against stored admin key when co-signing is required
+    pub admin_signer: Option<UncheckedAccount<'info>>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(
+        mut,
+        constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch
+    )]
+    pub epoch: Account<'info, Epoch>,
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+    /// CHECK: address constrained to the vault's stored treasury
+    #[account(mut, address = reward_vault.treasury @ RewardVaultError::InvalidTreasury)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = RecipientStats::LEN,
+        seeds = [RecipientStats::SEED, reward_vault.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub recipient_stats: Account<'info, RecipientStats>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Close Vault Context
+ * 
+ * Accounts required for closing a vault including admin signer, vault
+ * account, the vault token account for SPL vaults, and the treasury
+ * accounts used when sweeping epochs. Epochs to sweep are supplied as
+ * writable remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    /// CHECK: matched against the vault's stored treasury before sweeping
+    #[account(mut)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub destination_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+/**
+ * Refund To Vault Context
+ * 
+ * Accounts required for returning a payout including recipient signer,
+ * distributor signer, vault, epoch, token accounts for SPL vaults, and
+ * the recipient and vault stats accounts when they exist.
+ */
+#[derive(Accounts)]
+#[instruction(amount: u64, epoch_index: u64)]
+pub struct RefundToVault<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    /// CHECK: verified as signer against stored distributor key
+    pub distributor_signer: AccountInfo<'info>,
+    pub session_key: Option<Account<'info, SessionKey>>,
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        seeds = [Epoch::SEED, reward_vault.key().as_ref(), &Epoch::index_seed(epoch_index)],
+        bump = epoch.bump
+    )]
+    pub epoch: Account<'info, Epoch>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [RecipientStats::SEED, reward_vault.key().as_ref(), recipient.key().as_ref()],
+        bump = recipient_stats.bump
+    )]
+    pub recipient_stats: Option<Account<'info, RecipientStats>>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Get Distributors Context
+ * 
+ * Accounts required for the read-only distributor view including the
+ * vault and an optional epoch whose override should be included.
+ */
+#[derive(Accounts)]
+pub struct GetDistributors<'info> {
+    #[account(
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    pub epoch: Option<Account<'info, Epoch>>,
+}
+
+/**
+ * Escrow Reward Context
+ * 
+ * Accounts required for escrowing a reward including vault account,
+ * distributor signer, the escrow PDA, and its rent payer.
+ */
+#[derive(Accounts)]
+#[instruction(recipient: Pubkey)]
+pub struct EscrowReward<'info> {
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    /// CHECK: verified as signer against stored distributor key
+    pub distributor_signer: AccountInfo<'info>,
+    pub session_key: Option<Account<'info, SessionKey>>,
+    /// CHECK: verified as signer against stored admin key when co-signing is required
+    pub admin_signer: Option<UncheckedAccount<'info>>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(
+        init,
+        payer = payer,
+        space = EscrowedReward::LEN,
+        seeds = [EscrowedReward::SEED, reward_vault.key().as_ref(), recipient.as_ref()],
+        bump
+    )]
+    pub escrowed_reward: Account<'info, EscrowedReward>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Challenge Reward Context
+ * 
+ * Accounts required for challenging an escrow including admin signer,
+ * vault account, the escrow being cancelled, and its rent payer.
+ */
+#[derive(Accounts)]
+pub struct ChallengeReward<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        seeds = [EscrowedReward::SEED, reward_vault.key().as_ref(), escrowed_reward.recipient.as_ref()],
+        bump = escrowed_reward.bump
+    )]
+    pub escrowed_reward: Account<'info, EscrowedReward>,
+    /// CHECK: rent refund destination, matched against the escrow's payer
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+/**
+ * Finalize Reward Context
+ * 
+ * Accounts required for paying out an escrow including vault account,
+ * the escrow being settled, its recipient, and its rent payer.
+ */
+#[derive(Accounts)]
+pub struct FinalizeReward<'info> {
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        has_one = recipient,
+        seeds = [EscrowedReward::SEED, reward_vault.key().as_ref(), escrowed_reward.recipient.as_ref()],
+        bump = escrowed_reward.bump
+    )]
+    pub escrowed_reward: Account<'info, EscrowedReward>,
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+    /// CHECK: rent refund destination, matched against the escrow's payer
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Withdraw Contribution Context
+ * 
+ * Accounts required for withdrawing a funding contribution including
+ * funder signer, vault, epoch, contribution PDA, and token accounts for
+ * SPL vaults.
+ */
+#[derive(Accounts)]
+pub struct WithdrawContribution<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch
+    )]
+    pub epoch: Account<'info, Epoch>,
+    #[account(
+        mut,
+        has_one = funder,
+        has_one = epoch,
+        seeds = [FunderContribution::SEED, epoch.key().as_ref(), funder.key().as_ref()],
+        bump = funder_contribution.bump
+    )]
+    pub funder_contribution: Account<'info, FunderContribution>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub funder_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Reclaim Contribution Context
+ * 
+ * Accounts required for reclaiming a contribution to a cancelled epoch
+ * including funder signer, vault, the closed epoch address, contribution
+ * PDA, and token accounts for SPL vaults.
+ */
+#[derive(Accounts)]
+#[instruction(epoch_index: u64)]
+pub struct ReclaimContribution<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    /// CHECK: address tied to the vault by seeds; must be closed, checked in reclaim_contribution
+    #[account(
+        seeds = [Epoch::SEED, reward_vault.key().as_ref(), &Epoch::index_seed(epoch_index)],
+        bump
+    )]
+    pub epoch: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = funder,
+        has_one = funder,
+        has_one = epoch,
+        seeds = [FunderContribution::SEED, epoch.key().as_ref(), funder.key().as_ref()],
+        bump = funder_contribution.bump
+    )]
+    pub funder_contribution: Account<'info, FunderContribution>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub funder_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Migrate Epoch Context
+ * 
+ * Accounts required for migrating an epoch's layout including admin
+ * signer, vault account, the epoch at its legacy address, and the
+ * indexed address it moves to. Both are unchecked because the old layout
+ * cannot be deserialized as Epoch and the new account does not exist yet.
+ */
+#[derive(Accounts)]
+pub struct MigrateEpoch<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    /// CHECK: owner and discriminator verified in migrate_epoch
+    #[account(
+        mut,
+        seeds = [Epoch::SEED, reward_vault.key().as_ref()],
+        bump
+    )]
+    pub legacy_epoch: UncheckedAccount<'info>,
+    /// CHECK: address verified against the legacy epoch's index in migrate_epoch
+    #[account(mut)]
+    pub epoch: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Migrate Vault Context
+ * 
+ * Accounts required for migrating a vault's layout including admin signer,
+ * the vault being migrated, and the reward mint for SPL vaults. The vault
+ * is unchecked because its old layout cannot be deserialized as
+ * RewardVault.
+ */
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: owner, discriminator, admin, and address verified in migrate_vault
+    #[account(mut)]
+    pub reward_vault: UncheckedAccount<'info>,
+    /// CHECK: unpacked and matched against reward_mint in resolve_mint_decimals
+    pub mint: Option<UncheckedAccount<'info>>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Configure Vault Context
+ * 
+ * Accounts required for non-destructive vault configuration, which either
+ * the admin or the operator may sign.
+ */
+#[derive(Accounts)]
+pub struct ConfigureVault<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = authority.key() == reward_vault.admin
+            || Some(authority.key()) == reward_vault.operator @ RewardVaultError::UnauthorizedOperator,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+}
+
+/**
+ * Register Recipient Context
+ * 
+ * Accounts required for registering a recipient including admin signer,
+ * vault account, and the registry PDA, created on first use.
+ */
+#[derive(Accounts)]
+pub struct RegisterRecipient<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = RecipientRegistry::LEN,
+        seeds = [RecipientRegistry::SEED, reward_vault.key().as_ref()],
+        bump
+    )]
+    pub recipient_registry: Account<'info, RecipientRegistry>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Disburse To Registered Context
+ * 
+ * Accounts required for paying a registered recipient including vault,
+ * distributor signer, registry, recipient, recipient stats, and token
+ * accounts for SPL vaults.
+ */
+#[derive(Accounts)]
+pub struct DisburseToRegistered<'info> {
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    /// CHECK: verified as signer against stored distributor key
+    pub distributor_signer: AccountInfo<'info>,
+    pub session_key: Option<Account<'info, SessionKey>>,
+    /// CHECK: verified as signer against stored admin key when co-signing is required
+    pub admin_signer: Option<UncheckedAccount<'info>>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(
+        seeds = [RecipientRegistry::SEED, reward_vault.key().as_ref()],
+        bump = recipient_registry.bump
+    )]
+    pub recipient_registry: Account<'info, RecipientRegistry>,
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+    /// CHECK: address constrained to the vault's stored treasury
+    #[account(mut, address = reward_vault.treasury @ RewardVaultError::InvalidTreasury)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = RecipientStats::LEN,
+        seeds = [RecipientStats::SEED, reward_vault.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub recipient_stats: Account<'info, RecipientStats>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Get Scaled Amount Context
+ * 
+ * Accounts required for the read-only amount scaling view.
+ */
+#[derive(Accounts)]
+pub struct GetScaledAmount<'info> {
+    #[account(
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+}
+
+/**
+ * Update Blocklist Context
+ * 
+ * Accounts required for editing the blocklist including the admin or
+ * operator signer, vault account, and the blocklist PDA, created on first
+ * use.
+ */
+#[derive(Accounts)]
+pub struct UpdateBlocklist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = authority.key() == reward_vault.admin
+            || Some(authority.key()) == reward_vault.operator @ RewardVaultError::UnauthorizedOperator,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Blocklist::LEN,
+        seeds = [Blocklist::SEED, reward_vault.key().as_ref()],
+        bump
+    )]
+    pub blocklist: Account<'info, Blocklist>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Claim Relayed Context
+ * 
+ * Accounts required for a relayed Merkle claim including the relayer
+ * signer who pays fees and rent, the recipient, vault, epoch, the claim
+ * PDA, and token accounts for SPL vaults.
+ */
+#[derive(Accounts)]
+pub struct ClaimRelayed<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch
+    )]
+    pub epoch: Account<'info, Epoch>,
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = Claim::LEN,
+        seeds = [Claim::SEED, epoch.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, Claim>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub relayer_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub acknowledgment: Option<Account<'info, AcknowledgeTerms>>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Create Recipient ATAs Context
+ * 
+ * Accounts required for bulk ATA creation including the funder paying
+ * rent, vault account, reward mint, and programs. Recipient wallets and
+ * ATAs are supplied as remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct CreateRecipientAtas<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    #[account(
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(constraint = Some(mint.key()) == reward_vault.reward_mint @ RewardVaultError::MintMismatch)]
+    pub mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Init Audit Log Context
+ * 
+ * Accounts required for creating the audit log including admin signer,
+ * vault account, audit log PDA, and system program.
+ */
+#[derive(Accounts)]
+pub struct InitAuditLog<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        init,
+        payer = admin,
+        space = AuditLog::LEN,
+        seeds = [AuditLog::SEED, reward_vault.key().as_ref()],
+        bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Get Audit Log Context
+ * 
+ * Accounts required for the read-only recent disbursements view.
+ */
+#[derive(Accounts)]
+pub struct GetAuditLog<'info> {
+    pub audit_log: Account<'info, AuditLog>,
+}
+
+/**
+ * Init Vault Stats Context
+ * 
+ * Accounts required for creating the stats account including admin
+ * signer, vault account, stats PDA, and system program.
+ */
+#[derive(Accounts)]
+pub struct InitVaultStats<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        init,
+        payer = admin,
+        space = VaultStats::LEN,
+        seeds = [VaultStats::SEED, reward_vault.key().as_ref()],
+        bump
+    )]
+    pub vault_stats: Account<'info, VaultStats>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Get Vault Stats Context
+ * 
+ * Accounts required for the read-only vault statistics view.
+ */
+#[derive(Accounts)]
+pub struct GetVaultStats<'info> {
+    pub vault_stats: Account<'info, VaultStats>,
+}
+
+/**
+ * Init Sub-Epoch Context
+ * 
+ * Accounts required for creating a claim shard including admin signer,
+ * vault account, the parent epoch, and the new sub-epoch PDA.
+ */
+#[derive(Accounts)]
+#[instruction(shard: u16)]
+pub struct InitSubEpoch<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch
+    )]
+    pub epoch: Account<'info, Epoch>,
+    #[account(
+        init,
+        payer = admin,
+        space = SubEpoch::LEN,
+        seeds = [SubEpoch::SEED, epoch.key().as_ref(), &shard.to_le_bytes()],
+        bump
+    )]
+    pub sub_epoch: Account<'info, SubEpoch>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Claim Sub-Epoch Context
+ * 
+ * Accounts required for a sharded Merkle claim including recipient
+ * signer, vault, the read-only epoch, the sub-epoch, the claim PDA, and
+ * token accounts for SPL vaults. The claim PDA is seeded with the shard
+ * number after the epoch and recipient.
+ */
+#[derive(Accounts)]
+pub struct ClaimSubEpoch<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch)]
+    pub epoch: Account<'info, Epoch>,
+    #[account(
+        mut,
+        seeds = [SubEpoch::SEED, epoch.key().as_ref(), &sub_epoch.shard.to_le_bytes()],
+        bump = sub_epoch.bump
+    )]
+    pub sub_epoch: Account<'info, SubEpoch>,
+    #[account(
+        init_if_needed,
+        payer = recipient,
+        space = Claim::LEN,
+        seeds = [Claim::SEED, epoch.key().as_ref(), recipient.key().as_ref(), &sub_epoch.shard.to_le_bytes()],
+        bump
+    )]
+    pub claim: Account<'info, Claim>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub acknowledgment: Option<Account<'info, AcknowledgeTerms>>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Sync Sub-Epoch Context
+ * 
+ * Accounts required for folding a sub-epoch's payouts into its epoch.
+ */
+#[derive(Accounts)]
+pub struct SyncSubEpoch<'info> {
+    #[account(mut)]
+    pub epoch: Account<'info, Epoch>,
+    #[account(
+        mut,
+        seeds = [SubEpoch::SEED, epoch.key().as_ref(), &sub_epoch.shard.to_le_bytes()],
+        bump = sub_epoch.bump
+    )]
+    pub sub_epoch: Account<'info, SubEpoch>,
+}
+
+/**
+ * Register Session Key Context
+ * 
+ * Accounts required for delegating to a session key including the
+ * distributor signer, vault account, and the new session key PDA.
+ */
+#[derive(Accounts)]
+#[instruction(session: Pubkey)]
+pub struct RegisterSessionKey<'info> {
+    #[account(mut)]
+    pub distributor: Signer<'info>,
+    #[account(
+        has_one = distributor @ RewardVaultError::UnauthorizedDistributor,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        init,
+        payer = distributor,
+        space = SessionKey::LEN,
+        seeds = [SessionKey::SEED, reward_vault.key().as_ref(), session.as_ref()],
+        bump
+    )]
+    pub session_key: Account<'info, SessionKey>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Revoke Session Key Context
+ * 
+ * Accounts required for revoking a session key including the signing
+ * distributor or admin, vault account, and the session key PDA to close.
+ */
+#[derive(Accounts)]
+pub struct RevokeSessionKey<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == session_key.distributor
+            || authority.key() == reward_vault.admin @ RewardVaultError::UnauthorizedDistributor
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        close = authority,
+        constraint = session_key.vault == reward_vault.key() @ RewardVaultError::InvalidSessionKey,
+        seeds = [SessionKey::SEED, reward_vault.key().as_ref(), session_key.session.as_ref()],
+        bump = session_key.bump
+    )]
+    pub session_key: Account<'info, SessionKey>,
+}
+
+/**
+ * Next Claimable Context
+ * 
+ * Accounts required for the next-claimable view. Epoch and claim
+ * accounts are supplied as remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct NextClaimable<'info> {
+    #[account(
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+}
+
+/**
+ * Reassign Epoch Context
+ * 
+ * Accounts required for moving an epoch between vaults including both
+ * admin signers, both vault accounts, the epoch and its replacement under
+ * the new vault, and both canonical vault token accounts for SPL vaults.
+ * The old vault's admin pays rent for the new epoch.
+ */
+#[derive(Accounts)]
+#[instruction(new_vault: Pubkey, new_index: u64)]
+pub struct ReassignEpoch<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    pub new_admin: Signer<'info>,
+    #[account(
+        mut,
+        constraint = new_vault.admin == new_admin.key() @ RewardVaultError::UnauthorizedAdmin,
+        seeds = [RewardVault::SEED, new_vault.admin.as_ref()],
+        bump = new_vault.bump
+    )]
+    pub new_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch
+    )]
+    pub epoch: Account<'info, Epoch>,
+    #[account(
+        init,
+        payer = admin,
+        space = Epoch::LEN,
+        seeds = [Epoch::SEED, new_vault.key().as_ref(), &Epoch::index_seed(new_index)],
+        bump
+    )]
+    pub new_epoch: Account<'info, Epoch>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub new_vault_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Set Claim Delegate Context
+ * 
+ * Accounts required for naming a claim delegate including the recipient
+ * signer, vault account, and the claim delegate PDA, created on first use.
+ */
+#[derive(Accounts)]
+pub struct SetClaimDelegate<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    #[account(
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        init_if_needed,
+        payer = recipient,
+        space = ClaimDelegate::LEN,
+        seeds = [ClaimDelegate::SEED, reward_vault.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub claim_delegate: Account<'info, ClaimDelegate>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Claim Delegated Context
+ * 
+ * Accounts required for a delegate-signed Merkle claim including the
+ * delegate signer who pays fees and rent, the recipient and their claim
+ * delegate PDA, vault, epoch, the claim PDA, and token accounts for SPL
+ * vaults.
+ */
+#[derive(Accounts)]
+pub struct ClaimDelegated<'info> {
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        seeds = [ClaimDelegate::SEED, reward_vault.key().as_ref(), recipient.key().as_ref()],
+        bump = claim_delegate.bump
+    )]
+    pub claim_delegate: Account<'info, ClaimDelegate>,
+    #[account(
+        mut,
+        constraint = epoch.vault == reward_vault.key() @ RewardVaultError::EpochMismatch
+    )]
+    pub epoch: Account<'info, Epoch>,
+    #[account(
+        init_if_needed,
+        payer = delegate,
+        space = Claim::LEN,
+        seeds = [Claim::SEED, epoch.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, Claim>,
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub acknowledgment: Option<Account<'info, AcknowledgeTerms>>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Crank Epoch Context
+ * 
+ * Accounts required for advancing a recurring epoch including the payer
+ * covering rent, vault account, the previous epoch, and the next epoch
+ * PDA at the following index.
+ */
+#[derive(Accounts)]
+pub struct CrankEpoch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        seeds = [Epoch::SEED, reward_vault.key().as_ref(), &Epoch::index_seed(prev_epoch.index)],
+        bump = prev_epoch.bump
+    )]
+    pub prev_epoch: Account<'info, Epoch>,
+    #[account(
+        init,
+        payer = payer,
+        space = Epoch::LEN,
+        seeds = [Epoch::SEED, reward_vault.key().as_ref(), &Epoch::index_seed(prev_epoch.index.wrapping_add(1))],
+        bump
+    )]
+    pub epoch: Account<'info, Epoch>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Acknowledge Context
+ * 
+ * Accounts required for acknowledging terms including the recipient
+ * signer and the acknowledgment PDA for the terms hash.
+ */
+#[derive(Accounts)]
+#[instruction(terms_hash: [u8; 32])]
+pub struct Acknowledge<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    #[account(
+        init,
+        payer = recipient,
+        space = AcknowledgeTerms::LEN,
+        seeds = [AcknowledgeTerms::SEED, recipient.key().as_ref(), terms_hash.as_ref()],
+        bump
+    )]
+    pub acknowledgment: Account<'info, AcknowledgeTerms>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Disburse SPL Batch Context
+ * 
+ * Accounts required for batched SPL disbursement including vault account,
+ * distributor signer, optional epoch, reward mint, vault token account,
+ * payer for new recipient stats, and token program. Recipient token
+ * accounts and their owners' stats PDAs are supplied as writable
+ * remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct DisburseSplBatch<'info> {
+    #[account(
+        mut,
+        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
+        bump = reward_vault.bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    /// CHECK: verified as signer against stored distributor key
+    pub distributor_signer: AccountInfo<'info>,
+    pub session_key: Option<Account<'info, SessionKey>>,
+    /// CHECK: verified as signer against stored admin key when co-signing is required
+    pub admin_signer: Option<UncheckedAccount<'info>>,
+    pub blocklist: Option<Account<'info, Blocklist>>,
+    #[account(mut)]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+    #[account(mut)]
+    pub epoch: Option<Account<'info, Epoch>>,
+    #[account(constraint = Some(mint.key()) == reward_vault.reward_mint @ RewardVaultError::MintMismatch)]
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub vault_stats: Option<Account<'info, VaultStats>>,
+}
+
+/**
+ * Derive Vault PDA Context
+ * 
+ * The vault PDA derivation view reads no accounts.
+ */
+#[derive(Accounts)]
+pub struct DeriveVaultPda {}
+
+// ============================================================================
+// DATA STRUCTURES
+// ============================================================================
+
+/**
+ * Reward Vault Account
+ * 
+ * Stores configuration and state for a reward vault including
+ * admin authority, distributor authorization, and payment mode.
+ */
+#[account]
+pub struct RewardVault {
+    pub admin: Pubkey,              // Admin authority for vault management
+    pub distributor: Pubkey,        // Authorized distributor for rewards
+    pub reward_mint: Option<Pubkey>, // SPL token mint (if not SOL)
+    pub pay_sol: bool,              // Whether to pay in SOL or SPL tokens
+    pub min_disbursement: u64,      // Smallest allowed disbursement amount
+    pub funder_allowlist_root: [u8; 32], // Merkle root of allowed funders (zeroed = open)
+    pub max_epochs: u64,            // Maximum epoch count (0 = unlimited)
+    pub disburse_cooldown: i64,     // Minimum seconds between disbursements (0 = off)
+    pub last_disburse_ts: i64,      // Timestamp of the most recent disbursement
+    pub treasury: Pubkey,           // Destination for swept funds
+    pub recipient_cooldown: i64,    // Minimum seconds between payouts to one recipient (0 = off)
+    pub non_overlapping_epochs: bool, // Whether new epochs must start after the latest epoch end
+    pub last_epoch_end: i64,        // Latest end timestamp of any epoch created
+    pub emit_events: bool,          // Whether disbursements and funding emit events
+    pub mint_decimals: u8,          // Decimals of the payout asset
+    pub operator: Option<Pubkey>,   // Key allowed non-destructive configuration changes
+    pub paused: bool,               // Whether disbursements are halted
+    pub blocklist_active: bool,     // Whether the blocklist has entries to enforce
+    pub require_admin_cosign: bool, // Whether disbursements need the admin's signature
+    pub max_relayer_fee: u64,       // Largest fee a relayer may deduct from a claim
+    pub reward_tiers: Vec<u64>,     // Allowed disbursement amounts (empty = any amount)
+    pub audit_log_active: bool,     // Whether disbursements are recorded in the audit log
+    pub sol_reserve: u64,           // Lamports SOL payouts keep above rent (0 = rent only)
+    pub stats_active: bool,         // Whether counters are kept in the stats account
+    pub require_funded_epoch: bool, // Whether disbursements need a funded epoch
+    pub debug_profiling: bool,      // Whether payout paths log compute units
+    pub outstanding_obligations: u64, // Open escrows that still hold vault funds
+    pub fee_bps: u16,               // Protocol fee on disbursements, paid to the treasury
+    pub fee_exempt_root: [u8; 32],  // Merkle root of fee-exempt recipients (zeroed = none)
+    pub epoch_cadence: i64,         // Length of epochs created by crank_epoch (0 = disabled)
+    pub max_disburse_bps: u16,      // Largest single payout as a share of the vault balance
+    pub require_memo: bool,         // Whether every payout must carry a memo
+    pub name: [u8; 32],             // UTF-8 display name, zero-padded
+    pub metadata_uri: [u8; 128],    // UTF-8 metadata URI, zero-padded
+    pub config_hash: [u8; 32],      // Keccak commitment to the admin-set configuration
+    pub version: u8,                // Account layout version
+    pub bump: u8,                   // Bump seed for PDA
+}
+
+impl RewardVault {
+    pub const SEED: &'static [u8] = b"reward_vault";
+    pub const LEN: usize = 8 + 32 + 32 + 33 + 1 + 8 + 32 + 8 + 8 + 8 + 32 + 8 + 1 + 8 + 1 + 1 + 33 + 1 + 1 + 1 + 8 + (4 + 8 * Self::MAX_REWARD_TIERS) + 1 + 8 + 1 + 1 + 1 + 8 + 2 + 32 + 8 + 2 + 1 + Self::MAX_NAME_LEN + Self::MAX_METADATA_URI_LEN + 32 + 1 + 1; // Discriminator + fields
+    pub const VERSION: u8 = 2; // Current account layout version
+    pub const MAX_SINGLE_FUND: u64 = 1_000_000_000_000_000_000; // 1e9 whole units at 9 decimals
+    pub const MAX_MEMO_LEN: usize = 64; // Bytes allowed in a disbursement memo
+    pub const MAX_EPOCH_BATCH: usize = 12; // Epochs created per start_epochs call, bounded by compute
+    // Entries per batch instruction (disburse_sol_batch, disburse_spl_batch, disburse_split,
+    // claim_many, close_vault sweeps), whether an entry spans one remaining account or a pair.
+    // Each entry costs a deserialize or lamport move plus its checks, so 20 keeps batches inside
+    // the 200k default compute budget; paired batches that large need an address lookup table.
+    pub const MAX_BATCH: usize = 20;
+    pub const SOL_DECIMALS: u8 = 9; // Decimals of native SOL
+    pub const MAX_ATA_BATCH: usize = 16; // ATAs created per create_recipient_atas call, bounded by compute
+    pub const MAX_REWARD_TIERS: usize = 8; // Reward tiers stored on the vault
+    pub const MAX_FEE_BPS: u16 = 1_000; // Highest protocol fee, 10%
+    pub const FEE_BPS_SCALE: u128 = 10_000; // Basis-point denominator for fee_bps
+    pub const FULL_DISBURSE_BPS: u16 = 10_000; // max_disburse_bps value meaning no cap
+    pub const MAX_NAME_LEN: usize = 32; // Bytes in the vault display name
+    pub const MAX_METADATA_URI_LEN: usize = 128; // Bytes in the vault metadata URI
+
+    /// Keccak hash over the admin-set configuration fields, in declaration order.
+    /// Runtime state such as `last_disburse_ts` and `outstanding_obligations` is excluded.
+    pub fn compute_config_hash(&self) -> [u8; 32] {
+        let reward_mint = self.reward_mint.unwrap_or_default();
+        let reward_tiers: Vec<u8> = self.reward_tiers.iter().flat_map(|tier| tier.to_le_bytes()).collect();
+        keccak::hashv(&[
+            self.admin.as_ref(),
+            self.distributor.as_ref(),
+            &[u8::from(self.reward_mint.is_some())],
+            reward_mint.as_ref(),
+            &[u8::from(self.pay_sol)],
+            &self.min_disbursement.to_le_bytes(),
+            &self.funder_allowlist_root,
+            &self.max_epochs.to_le_bytes(),
+            &self.disburse_cooldown.to_le_bytes(),
+            self.treasury.as_ref(),
+            &self.recipient_cooldown.to_le_bytes(),
+            &[u8::from(self.non_overlapping_epochs)],
+            &[u8::from(self.emit_events)],
+            &[self.mint_decimals],
+            &[u8::from(self.operator.is_some())],
+            self.operator.unwrap_or_default().as_ref(),
+            &[u8::from(self.paused)],
+            &[u8::from(self.blocklist_active)],
+            &[u8::from(self.require_admin_cosign)],
+            &self.max_relayer_fee.to_le_bytes(),
+            &(self.reward_tiers.len() as u32).to_le_bytes(),
+            &reward_tiers,
+            &[u8::from(self.audit_log_active)],
+            &self.sol_reserve.to_le_bytes(),
+            &[u8::from(self.stats_active)],
+            &[u8::from(self.require_funded_epoch)],
+            &[u8::from(self.debug_profiling)],
+            &self.fee_bps.to_le_bytes(),
+            &self.fee_exempt_root,
+            &self.epoch_cadence.to_le_bytes(),
+            &self.max_disburse_bps.to_le_bytes(),
+            &[u8::from(self.require_memo)],
+            &self.name,
+            &self.metadata_uri,
+        ])
+        .0
+    }
+
+    /// Recompute the stored configuration hash after an admin change.
+    pub fn refresh_config_hash(&mut self) {
+        self.config_hash = self.compute_config_hash();
+    }
+}
+
+/**
+ * Epoch Account
+ * 
+ * Tracks reward distribution for a specific time period including
+ * time boundaries, funding totals, and sequential indexing.
+ */
+#[account]
+pub struct Epoch {
+    pub vault: Pubkey,        // Associated vault account
+    pub start_ts: i64,        // Epoch start timestamp
+    pub end_ts: i64,          // Epoch end timestamp
+    pub index: u64,           // Sequential epoch number
+    pub total_funded: u128,   // Total amount funded for this epoch
+    pub total_disbursed: u128, // Total amount paid out against this epoch
+    pub allowlist_root: [u8; 32], // Merkle root of allowed recipients (zeroed = open)
+    pub claim_grace_seconds: i64, // Seconds after end_ts before sweeping is allowed
+    pub status: EpochStatus,  // Lifecycle status of the epoch
+    pub rolled_over: bool,    // Whether unspent funds rolled into another epoch
+    pub epoch_distributor: Option<Pubkey>, // Distributor override for this epoch
+    pub funding_cap: u128,    // Funding target for this epoch (0 = no cap)
+    pub funding_cap_reached: bool, // Whether the funding cap event has fired
+    pub hard_cap: u128,       // Most the epoch may ever be funded with (0 = unlimited)
+    pub funding_commitment: [u8; 32], // Hash of the committed funding amount and salt (zeroed = none)
+    pub revealed_funding: Option<u64>, // Funding amount once the commitment is revealed
+    pub finalized_amount: u128, // total_funded frozen by finalize_funding
+    pub funding_locked: bool, // Whether funding is finalized and no longer changes
+    pub terms_hash: [u8; 32], // Terms claimants must acknowledge first (zeroed = none)
+    pub claim_root: [u8; 32], // Merkle root of claim allocations (zeroed = disabled)
+    pub per_recipient_amount: u64, // Fixed payout per recipient for disburse_fixed
+    pub remaining_slots: u64, // Fixed payouts left before the epoch is fully paid
+    pub payout_rate: Option<u64>, // Funded-to-payout conversion rate (None = same asset)
+    pub claim_deadline: i64,  // Last timestamp claims are accepted (0 = no deadline)
+    pub sub_epoch_count: u16, // Number of claim shards created for this epoch
+    pub max_proof_depth: u8,  // Longest Merkle proof accepted by claims
+    pub reward_multiplier_bps: u16, // Multiplier on disbursed base amounts (10000 = 1x)
+    pub version: u8,          // Account layout version
+    pub bump: u8,             // Bump seed for PDA
+}
+
+impl Epoch {
+    pub const SEED: &'static [u8] = b"epoch";
+    pub const VAULT_SEED: &'static [u8] = b"epoch_vault";
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 16 + 16 + 32 + 8 + 1 + 1 + 33 + 16 + 1 + 16 + 32 + 9 + 16 + 1 + 32 + 32 + 8 + 8 + 9 + 8 + 2 + 1 + 2 + 1 + 1; // Discriminator + fields
+    pub const VERSION: u8 = 1; // Current account layout version
+    pub const RATE_SCALE: u128 = 1_000_000_000; // Fixed-point scale of payout_rate
+    pub const APR_SCALE: u128 = 1_000_000_000; // Fixed-point scale of annualized rates (1e9 = 100%)
+    pub const SECONDS_PER_YEAR: u128 = 31_536_000; // 365-day year used to annualize rates
+    pub const DEFAULT_MAX_PROOF_DEPTH: u8 = 20; // Fits trees of up to 2^20 (~1M) leaves
+    pub const MAX_PROOF_DEPTH_LIMIT: u8 = 32; // Highest depth cap an epoch may be given
+    pub const MULTIPLIER_SCALE: u16 = 10_000; // reward_multiplier_bps value meaning 1x
+
+    /// Epoch PDAs are seeded with the index as 8 little-endian bytes:
+    /// `[b"epoch", vault, index.to_le_bytes()]`. Clients must match exactly.
+    pub fn index_seed(index: u64) -> [u8; 8] {
+        index.to_le_bytes()
+    }
+
+    /// Derive the address epochs had before the index was part of the seed:
+    /// `[b"epoch", vault]`, one per vault. migrate_epoch moves such an epoch
+    /// to its indexed address.
+    pub fn find_legacy_address(vault: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, vault.as_ref()], &crate::ID)
+    }
+
+    /// Derive the epoch PDA and bump for a vault and epoch index.
+    pub fn find_address(vault: &Pubkey, index: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, vault.as_ref(), &Self::index_seed(index)], &crate::ID)
+    }
+
+    /// Funding that claim math is based on: the frozen `finalized_amount`
+    /// once funding is locked, else the live `total_funded`.
+    pub fn distributable_funding(&self) -> u128 {
+        if self.funding_locked {
+            self.finalized_amount
+        } else {
+            self.total_funded
+        }
+    }
+
+    /// Status at `now`. The stored status only records manual transitions:
+    /// activate_epoch can open an epoch early and finalize_epoch can end one
+    /// early. Otherwise the epoch is Active from start_ts until end_ts.
+    pub fn effective_status(&self, now: i64) -> EpochStatus {
+        match self.status {
+            EpochStatus::Swept => EpochStatus::Swept,
+            EpochStatus::Ended => EpochStatus::Ended,
+            _ if now >= self.end_ts => EpochStatus::Ended,
+            EpochStatus::Active => EpochStatus::Active,
+            EpochStatus::Pending if now >= self.start_ts => EpochStatus::Active,
+            EpochStatus::Pending => EpochStatus::Pending,
+        }
+    }
+
+    /// Amount the epoch may pay out: `total_funded`, converted at `payout_rate`
+    /// when set. Conversion rounds down.
+    pub fn payout_budget(&self) -> Result<u128> {
+        match self.payout_rate {
+            Some(rate) => Ok(self
+                .total_funded
+                .checked_mul(u128::from(rate))
+                .ok_or(RewardVaultError::ArithmeticOverflow)?
+                / Self::RATE_SCALE),
+            None => Ok(self.total_funded),
+        }
+    }
+
+    /// Simple annualized rate, scaled by `APR_SCALE`:
+    /// `total_funded * APR_SCALE * SECONDS_PER_YEAR / (staked_total * duration)`.
+    pub fn annualized_rate(&self, staked_total: u128) -> Result<u64> {
+        require!(staked_total > 0, RewardVaultError::ZeroStakedTotal);
+        let duration = u128::try_from(self.end_ts.saturating_sub(self.start_ts)).unwrap_or(0);
+        require!(duration > 0, RewardVaultError::InvalidEpochWindow);
+
+        let numerator = self
+            .total_funded
+            .checked_mul(Self::APR_SCALE)
+            .and_then(|value| value.checked_mul(Self::SECONDS_PER_YEAR))
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        let denominator = staked_total
+            .checked_mul(duration)
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+
+        u64::try_from(numerator / denominator).map_err(|_| RewardVaultError::ArithmeticOverflow.into())
+    }
+
+    /// Derive the per-epoch vault token account PDA: `[b"epoch_vault", epoch]`.
+    pub fn find_vault_token_address(epoch: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::VAULT_SEED, epoch.as_ref()], &crate::ID)
+    }
+}
+
+/**
+ * Stake Snapshot Account
+ * 
+ * Aggregates recorded stake weights for an epoch so proportional
+ * claims can be computed against the total.
+ */
+#[account]
+pub struct StakeSnapshot {
+    pub epoch: Pubkey,        // Associated epoch account
+    pub total_weight: u128,   // Sum of all recorded weights
+    pub rounding: RoundingMode, // How proportional shares are rounded
+    pub total_claimed: u128,  // Sum of all proportional claims paid
+    pub bump: u8,             // Bump seed for PDA
+}
+
+impl StakeSnapshot {
+    pub const SEED: &'static [u8] = b"stake_snapshot";
+    pub const LEN: usize = 8 + 32 + 16 + 1 + 16 + 1; // Discriminator + fields
+}
+
+/**
+ * Weight Account
+ * 
+ * Records a single recipient's stake weight within an epoch snapshot
+ * and how much of their proportional share has been claimed.
+ */
+#[account]
+pub struct Weight {
+    pub epoch: Pubkey,        // Associated epoch account
+    pub recipient: Pubkey,    // Recipient owning this weight
+    pub weight: u64,          // Recorded stake weight
+    pub claimed: u64,         // Amount already claimed
+    pub bump: u8,             // Bump seed for PDA
+}
+
+impl Weight {
+    pub const SEED: &'static [u8] = b"weight";
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 1; // Discriminator + fields
+}
+
+/**
+ * Recipient Stats Account
+ * 
+ * Tracks a recipient's lifetime earnings from a vault across all
+ * epochs for display purposes.
+ */
+#[account]
+pub struct RecipientStats {
+    pub vault: Pubkey,            // Associated vault account
+    pub recipient: Pubkey,        // Recipient these stats belong to
+    pub lifetime_received: u128,  // Total amount ever received
+    pub last_received_ts: i64,    // Timestamp of the most recent payout
+    pub bump: u8,                 // Bump seed for PDA
+}
+
+impl RecipientStats {
+    pub const SEED: &'static [u8] = b"stats";
+    pub const LEN: usize = 8 + 32 + 32 + 16 + 8 + 1; // Discriminator + fields
+
+    /// Take a refunded payout back out of the lifetime total.
+    pub fn record_refund(&mut self, amount: u64) {
+        self.lifetime_received = self.lifetime_received.saturating_sub(u128::from(amount));
+    }
+}
+
+/**
+ * Voucher Receipt Account
+ * 
+ * Marks a voucher nonce as redeemed so the same signed voucher cannot
+ * be paid out twice.
+ */
+#[account]
+pub struct VoucherReceipt {
+    pub vault: Pubkey,        // Vault the voucher was redeemed against
+    pub recipient: Pubkey,    // Recipient who redeemed the voucher
+    pub amount: u64,          // Amount paid out
+    pub nonce: u64,           // Voucher nonce
+    pub bump: u8,             // Bump seed for PDA
+}
+
+impl VoucherReceipt {
+    pub const SEED: &'static [u8] = b"voucher";
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 1; // Discriminator + fields
+}
+
+/**
+ * Epoch Summary
+ * 
+ * Return data for get_epoch_summary so clients don't duplicate the
+ * remaining-balance and activity arithmetic.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EpochSummary {
+    pub total_funded: u128,     // Total amount funded for the epoch
+    pub total_disbursed: u128,  // Total amount paid out against the epoch
+    pub remaining: u128,        // Funded minus disbursed, floored at zero
+    pub start_ts: i64,          // Epoch start timestamp
+    pub end_ts: i64,            // Epoch end timestamp
+    pub active: bool,           // Whether the clock is inside the epoch window
+}
+
+/**
+ * Claim Account
+ * 
+ * Records how much of their Merkle allocation a recipient has claimed from
+ * an epoch, preventing claims beyond the allocation.
+ */
+#[account]
+pub struct Claim {
+    pub epoch: Pubkey,        // Epoch the claim was made against
+    pub recipient: Pubkey,    // Recipient who claimed
+    pub amount: u64,          // Merkle-proven allocation
+    pub claimed: u64,         // Portion of the allocation paid so far
+    pub bump: u8,             // Bump seed for PDA
+}
+
+impl Claim {
+    pub const SEED: &'static [u8] = b"claim";
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 1; // Discriminator + fields
+
+    /// Part of the allocation not yet paid out.
+    pub fn remaining(&self) -> u64 {
+        self.amount.saturating_sub(self.claimed)
+    }
+
+    /// Read a claim PDA that may not exist yet; None if it was never created.
+    pub fn try_read(claim_info: &AccountInfo) -> Result<Option<Self>> {
+        if claim_info.owner != &crate::ID || claim_info.data_is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self::try_deserialize(&mut &claim_info.try_borrow_data()?[..])?))
+    }
+}
+
+/**
+ * Epoch Claim
+ * 
+ * One entry of a multi-epoch claim: the epoch, the allocated amount, and
+ * its Merkle proof.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EpochClaim {
+    pub epoch_index: u64,       // Index of the epoch being claimed
+    pub amount: u64,            // Allocated amount in the epoch claim tree
+    pub proof: Vec<[u8; 32]>,   // Merkle proof against the epoch claim root
+}
+
+/**
+ * Epoch Status
+ * 
+ * Lifecycle of an epoch: Pending until its start, Active while
+ * disbursing, Ended once its window closes or it is finalized, and Swept
+ * after unspent funds leave. See Epoch::effective_status.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EpochStatus {
+    Pending,
+    Active,
+    Ended,
+    Swept,
+}
+
+/**
+ * Rounding Mode
+ * 
+ * How proportional shares are rounded: down, up, or to the nearest unit
+ * with halves rounding up.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RoundingMode {
+    Floor,
+    Ceil,
+    Nearest,
+}
+
+impl RoundingMode {
+    /// Divide `numerator` by a non-zero `denominator` using this rounding mode.
+    pub fn divide(self, numerator: u128, denominator: u128) -> u128 {
+        let quotient = numerator / denominator;
+        let remainder = numerator % denominator;
+        let round_up = match self {
+            RoundingMode::Floor => false,
+            RoundingMode::Ceil => remainder > 0,
+            RoundingMode::Nearest => remainder >= denominator - remainder,
+        };
+
+        quotient + u128::from(round_up)
+    }
+}
+
+/**
+ * Epoch Spec
+ * 
+ * Parameters for one epoch in a batched start_epochs call.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EpochSpec {
+    pub start_ts: i64,                     // Epoch start timestamp
+    pub end_ts: i64,                       // Epoch end timestamp
+    pub index: u64,                        // Sequential epoch number
+    pub allowlist_root: [u8; 32],          // Merkle root of allowed recipients (zeroed = open)
+    pub claim_grace_seconds: i64,          // Seconds after end_ts before sweeping is allowed
+    pub epoch_distributor: Option<Pubkey>, // Distributor override for this epoch
+}
+
+/**
+ * Escrowed Reward Account
+ * 
+ * Holds a reward awaiting its challenge deadline. The escrowed lamports
+ * sit in this account on top of its rent.
+ */
+#[account]
+pub struct EscrowedReward {
+    pub vault: Pubkey,        // Vault the reward was escrowed from
+    pub recipient: Pubkey,    // Wallet paid on finalization
+    pub amount: u64,          // Escrowed amount in lamports
+    pub challenge_until: i64, // Deadline for an admin challenge
+    pub payer: Pubkey,        // Account that paid the escrow's rent
+    pub bump: u8,             // Bump seed for PDA
+}
+
+impl EscrowedReward {
+    pub const SEED: &'static [u8] = b"escrow";
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 32 + 1; // Discriminator + fields
+}
+
+/**
+ * Funder Contribution Account
+ * 
+ * Tracks how much a funder has put towards an epoch, bounding what they
+ * may withdraw before the epoch starts.
+ */
+#[account]
+pub struct FunderContribution {
+    pub epoch: Pubkey,        // Epoch the funds were committed to
+    pub funder: Pubkey,       // Wallet that funded
+    pub amount: u64,          // Outstanding contributed amount
+    pub bump: u8,             // Bump seed for PDA
+}
+
+impl FunderContribution {
+    pub const SEED: &'static [u8] = b"contribution";
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 1; // Discriminator + fields
+}
+
+/**
+ * Fund Receipt Account
+ * 
+ * Marks an idempotency key as used for a funder, so a retried fund_vault
+ * with the same key does not transfer twice.
+ */
+#[account]
+pub struct FundReceipt {
+    pub vault: Pubkey,              // Vault that was funded
+    pub funder: Pubkey,             // Wallet that funded
+    pub idempotency_key: [u8; 16],  // Client-supplied deduplication key
+    pub amount: u64,                // Amount funded under this key
+    pub bump: u8,                   // Bump seed for PDA
+}
+
+impl FundReceipt {
+    pub const SEED: &'static [u8] = b"fund_receipt";
+    pub const LEN: usize = 8 + 32 + 32 + 16 + 8 + 1; // Discriminator + fields
+}
+
+/**
+ * Epoch V0 Layout
+ * 
+ * Original Epoch layout, read by migrate_epoch when upgrading accounts
+ * created before later fields were added. Excludes the discriminator.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EpochV0 {
+    pub vault: Pubkey,        // Associated vault account
+    pub start_ts: i64,        // Epoch start timestamp
+    pub end_ts: i64,          // Epoch end timestamp
+    pub index: u64,           // Sequential epoch number
+    pub total_funded: u128,   // Total amount funded for this epoch
+    pub bump: u8,             // Bump seed for PDA
+}
+
+impl EpochV0 {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 16 + 1; // Discriminator + fields
+
+    /// Carry the original fields into the current layout with the new
+    /// fields zero-initialized. V0 had no stored status, so none is set and
+    /// the epoch keeps following its start_ts/end_ts window.
+    pub fn migrate(&self, bump: u8) -> Epoch {
+        let spec = EpochSpec {
+            start_ts: self.start_ts,
+            end_ts: self.end_ts,
+            index: self.index,
+            allowlist_root: [0u8; 32],
+            claim_grace_seconds: 0,
+            epoch_distributor: None,
+        };
+        let mut epoch = new_epoch(self.vault, &spec, bump);
+        epoch.total_funded = self.total_funded;
+        epoch
+    }
+}
+
+/**
+ * Reward Vault V0 Layout
+ * 
+ * Original RewardVault layout, read by migrate_vault when upgrading
+ * vaults created before later fields were added. Excludes the
+ * discriminator.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RewardVaultV0 {
+    pub admin: Pubkey,              // Admin authority for vault management
+    pub distributor: Pubkey,        // Authorized distributor for rewards
+    pub reward_mint: Option<Pubkey>, // SPL token mint (if not SOL)
+    pub pay_sol: bool,              // Whether to pay in SOL or SPL tokens
+    pub bump: u8,                   // Bump seed for PDA
+}
+
+impl RewardVaultV0 {
+    pub const LEN: usize = 8 + 32 + 32 + 33 + 1 + 1; // Discriminator + fields
+}
+
+/**
+ * Reward Vault V1 Layout
+ * 
+ * RewardVault layout before the name and metadata URI were added, read by
+ * migrate_vault. Excludes the discriminator.
  */
-#[derive(Accounts)]
-pub struct StartEpoch<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
-    #[account(
-        mut,
-        has_one = admin,
-        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
-        bump = reward_vault.bump
-    )]
-    pub reward_vault: Account<'info, RewardVault>,
-    #[account(
-        init,
-        payer = admin,
-        space = Epoch::LEN,
-        seeds = [Epoch::SEED, reward_vault.key().as_ref()],
-        bump
-    )]
-    pub epoch: Account<'info, Epoch>,
-    pub system_program: Program<'info, System>,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RewardVaultV1 {
+    pub admin: Pubkey,              // Admin authority for vault management
+    pub distributor: Pubkey,        // Authorized distributor for rewards
+    pub reward_mint: Option<Pubkey>, // SPL token mint (if not SOL)
+    pub pay_sol: bool,              // Whether to pay in SOL or SPL tokens
+    pub min_disbursement: u64,      // Smallest allowed disbursement amount
+    pub funder_allowlist_root: [u8; 32], // Merkle root of allowed funders (zeroed = open)
+    pub max_epochs: u64,            // Maximum epoch count (0 = unlimited)
+    pub disburse_cooldown: i64,     // Minimum seconds between disbursements (0 = off)
+    pub last_disburse_ts: i64,      // Timestamp of the most recent disbursement
+    pub treasury: Pubkey,           // Destination for swept funds
+    pub recipient_cooldown: i64,    // Minimum seconds between payouts to one recipient (0 = off)
+    pub non_overlapping_epochs: bool, // Whether new epochs must start after the latest epoch end
+    pub last_epoch_end: i64,        // Latest end timestamp of any epoch created
+    pub emit_events: bool,          // Whether disbursements and funding emit events
+    pub mint_decimals: u8,          // Decimals of the payout asset
+    pub operator: Option<Pubkey>,   // Key allowed non-destructive configuration changes
+    pub paused: bool,               // Whether disbursements are halted
+    pub blocklist_active: bool,     // Whether the blocklist has entries to enforce
+    pub require_admin_cosign: bool, // Whether disbursements need the admin's signature
+    pub max_relayer_fee: u64,       // Largest fee a relayer may deduct from a claim
+    pub reward_tiers: Vec<u64>,     // Allowed disbursement amounts (empty = any amount)
+    pub audit_log_active: bool,     // Whether disbursements are recorded in the audit log
+    pub sol_reserve: u64,           // Lamports SOL payouts keep above rent (0 = rent only)
+    pub stats_active: bool,         // Whether counters are kept in the stats account
+    pub require_funded_epoch: bool, // Whether disbursements need a funded epoch
+    pub config_hash: [u8; 32],      // Keccak commitment to the admin-set configuration
+    pub version: u8,                // Account layout version
+    pub bump: u8,                   // Bump seed for PDA
+}
+
+impl RewardVaultV1 {
+    pub const LEN: usize = 8 + 32 + 32 + 33 + 1 + 8 + 32 + 8 + 8 + 8 + 32 + 8 + 1 + 8 + 1 + 1 + 33 + 1 + 1 + 1 + 8 + (4 + 8 * RewardVault::MAX_REWARD_TIERS) + 1 + 8 + 1 + 1 + 32 + 1 + 1; // Discriminator + fields
+
+    /// Carry every field over to the current layout with empty metadata.
+    pub fn upgrade(self) -> RewardVault {
+        RewardVault {
+            admin: self.admin,
+            distributor: self.distributor,
+            reward_mint: self.reward_mint,
+            pay_sol: self.pay_sol,
+            min_disbursement: self.min_disbursement,
+            funder_allowlist_root: self.funder_allowlist_root,
+            max_epochs: self.max_epochs,
+            disburse_cooldown: self.disburse_cooldown,
+            last_disburse_ts: self.last_disburse_ts,
+            treasury: self.treasury,
+            recipient_cooldown: self.recipient_cooldown,
+            non_overlapping_epochs: self.non_overlapping_epochs,
+            last_epoch_end: self.last_epoch_end,
+            emit_events: self.emit_events,
+            mint_decimals: self.mint_decimals,
+            operator: self.operator,
+            paused: self.paused,
+            blocklist_active: self.blocklist_active,
+            require_admin_cosign: self.require_admin_cosign,
+            max_relayer_fee: self.max_relayer_fee,
+            reward_tiers: self.reward_tiers,
+            audit_log_active: self.audit_log_active,
+            sol_reserve: self.sol_reserve,
+            stats_active: self.stats_active,
+            require_funded_epoch: self.require_funded_epoch,
+            debug_profiling: false,
+            outstanding_obligations: 0,
+            fee_bps: 0,
+            fee_exempt_root: [0u8; 32],
+            epoch_cadence: 0,
+            max_disburse_bps: RewardVault::FULL_DISBURSE_BPS,
+            require_memo: false,
+            name: [0u8; 32],
+            metadata_uri: [0u8; 128],
+            config_hash: self.config_hash,
+            version: self.version,
+            bump: self.bump,
+        }
+    }
 }
 
 /**
- * Fund Vault Context
+ * Recipient Registry Account
  * 
- * Accounts required for vault funding including vault account,
- * funder signer, optional epoch tracking, and token accounts.
+ * Admin-curated list of recurring recipients that disbursements can
+ * reference by index.
  */
-#[derive(Accounts)]
-pub struct FundVault<'info> {
-    #[account(
-        mut,
-        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
-        bump = reward_vault.bump
-    )]
-    pub reward_vault: Account<'info, RewardVault>,
-    #[account(mut)]
-    pub funder: Signer<'info>,
-    #[account(mut)]
-    pub epoch: Option<Account<'info, Epoch>>,
-    #[account(mut)]
-    pub funder_token_account: Option<Account<'info, TokenAccount>>,
-    #[account(mut)]
-    pub vault_token_account: Option<Account<'info, TokenAccount>>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+#[account]
+pub struct RecipientRegistry {
+    pub vault: Pubkey,           // Vault the registry belongs to
+    pub recipients: Vec<Pubkey>, // Registered recipients in index order
+    pub bump: u8,                // Bump seed for PDA
+}
+
+impl RecipientRegistry {
+    pub const SEED: &'static [u8] = b"registry";
+    pub const MAX_RECIPIENTS: usize = 64; // Registry capacity
+    pub const LEN: usize = 8 + 32 + 4 + 32 * Self::MAX_RECIPIENTS + 1; // Discriminator + fields
 }
 
 /**
- * Disburse SOL Context
+ * Blocklist Account
  * 
- * Accounts required for SOL disbursement including vault account,
- * distributor signer, recipient account, and system program.
+ * Addresses the vault must never pay, checked directly on disbursement.
  */
-#[derive(Accounts)]
-pub struct DisburseSol<'info> {
-    #[account(
-        mut,
-        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
-        bump = reward_vault.bump
-    )]
-    pub reward_vault: Account<'info, RewardVault>,
-    /// CHECK: verified as signer against stored distributor key
-    pub distributor_signer: AccountInfo<'info>,
-    #[account(mut)]
-    pub recipient: SystemAccount<'info>,
-    pub system_program: Program<'info, System>,
+#[account]
+pub struct Blocklist {
+    pub vault: Pubkey,           // Vault the blocklist belongs to
+    pub entries: Vec<Pubkey>,    // Blocked recipient addresses
+    pub bump: u8,                // Bump seed for PDA
+}
+
+impl Blocklist {
+    pub const SEED: &'static [u8] = b"blocklist";
+    pub const MAX_ENTRIES: usize = 64; // Blocklist capacity
+    pub const LEN: usize = 8 + 32 + 4 + 32 * Self::MAX_ENTRIES + 1; // Discriminator + fields
 }
 
 /**
- * Disburse SPL Context
+ * Audit Log Account
  * 
- * Accounts required for SPL token disbursement including vault account,
- * distributor signer, token accounts, and token program.
+ * Ring buffer of the vault's most recent disbursements for on-chain
+ * auditing without an event indexer. Capacity keeps get_recent within the
+ * 1024-byte return data limit.
  */
-#[derive(Accounts)]
-pub struct DisburseSpl<'info> {
-    #[account(
-        mut,
-        seeds = [RewardVault::SEED, reward_vault.admin.as_ref()],
-        bump = reward_vault.bump
-    )]
-    pub reward_vault: Account<'info, RewardVault>,
-    /// CHECK: verified as signer against stored distributor key
-    pub distributor_signer: AccountInfo<'info>,
-    #[account(mut)]
-    pub vault_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub recipient_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+#[account]
+pub struct AuditLog {
+    pub vault: Pubkey,                            // Vault the log belongs to
+    pub head: u8,                                 // Slot the next entry is written to
+    pub len: u8,                                  // Number of filled slots
+    pub entries: [LogEntry; AuditLog::CAPACITY],  // Recorded disbursements
+    pub bump: u8,                                 // Bump seed for PDA
+}
+
+impl AuditLog {
+    pub const SEED: &'static [u8] = b"audit_log";
+    pub const CAPACITY: usize = 16; // Entries kept before the oldest is overwritten
+    pub const LEN: usize = 8 + 32 + 1 + 1 + LogEntry::LEN * Self::CAPACITY + 1; // Discriminator + fields
+
+    /// Write an entry at `head`, overwriting the oldest once the buffer is full.
+    pub fn record(&mut self, recipient: Pubkey, amount: u64, ts: i64) {
+        self.entries[self.head as usize] = LogEntry { recipient, amount, ts };
+        self.head = ((self.head as usize + 1) % Self::CAPACITY) as u8;
+        self.len = (self.len as usize + 1).min(Self::CAPACITY) as u8;
+    }
+
+    /// Filled entries ordered newest first.
+    pub fn recent(&self) -> Vec<LogEntry> {
+        (1..=self.len as usize)
+            .map(|offset| self.entries[(self.head as usize + Self::CAPACITY - offset) % Self::CAPACITY])
+            .collect()
+    }
+}
+
+/**
+ * Log Entry
+ * 
+ * A single disbursement recorded in the audit log.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct LogEntry {
+    pub recipient: Pubkey,      // Paid recipient
+    pub amount: u64,            // Amount paid
+    pub ts: i64,                // Unix timestamp of the payout
+}
+
+impl LogEntry {
+    pub const LEN: usize = 32 + 8 + 8;
+}
+
+/**
+ * Vault Stats Account
+ * 
+ * Aggregate counters for dashboards, kept up to date so clients don't
+ * have to scan every epoch.
+ */
+#[account]
+pub struct VaultStats {
+    pub vault: Pubkey,                  // Vault the stats belong to
+    pub total_epochs_created: u64,      // Epochs created since stats were enabled
+    pub total_funded_all_time: u128,    // Funding received since stats were enabled, net of withdrawals
+    pub total_disbursed_all_time: u128, // Amount paid out since stats were enabled
+    pub disbursement_count: u64,        // Individual payouts since stats were enabled
+    pub bump: u8,                       // Bump seed for PDA
+}
+
+impl VaultStats {
+    pub const SEED: &'static [u8] = b"vault_stats";
+    pub const LEN: usize = 8 + 32 + 8 + 16 + 16 + 8 + 1; // Discriminator + fields
+
+    /// Count newly created epochs.
+    pub fn record_epochs(&mut self, count: u64) -> Result<()> {
+        self.total_epochs_created = self
+            .total_epochs_created
+            .checked_add(count)
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Add received funding to the all-time total.
+    pub fn record_funding(&mut self, amount: u64) -> Result<()> {
+        self.total_funded_all_time = self
+            .total_funded_all_time
+            .checked_add(u128::from(amount))
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Take withdrawn funding back out of the all-time total. Saturates, since
+    /// the withdrawn funds may have arrived before stats were enabled.
+    pub fn record_withdrawal(&mut self, amount: u64) {
+        self.total_funded_all_time = self.total_funded_all_time.saturating_sub(u128::from(amount));
+    }
+
+    /// Take a refunded payout back out of the all-time disbursed total. The
+    /// payout count is kept, since a refund may return only part of one.
+    pub fn record_refund(&mut self, amount: u64) {
+        self.total_disbursed_all_time = self.total_disbursed_all_time.saturating_sub(u128::from(amount));
+    }
+
+    /// Add `count` payouts totalling `amount` to the all-time counters.
+    pub fn record_disbursements(&mut self, amount: u128, count: u64) -> Result<()> {
+        self.total_disbursed_all_time = self
+            .total_disbursed_all_time
+            .checked_add(amount)
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        self.disbursement_count = self
+            .disbursement_count
+            .checked_add(count)
+            .ok_or(RewardVaultError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/**
+ * Vault Stats Summary
+ * 
+ * Return data for get_vault_stats.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VaultStatsSummary {
+    pub total_epochs_created: u64,      // Epochs created since stats were enabled
+    pub total_funded_all_time: u128,    // Funding received since stats were enabled, net of withdrawals
+    pub total_disbursed_all_time: u128, // Amount paid out since stats were enabled
+    pub disbursement_count: u64,        // Individual payouts since stats were enabled
+}
+
+/**
+ * Vault Metadata
+ * 
+ * Display name and metadata URI supplied to init_vault and set_metadata.
+ * Both are raw UTF-8 bytes, checked against RewardVault::MAX_NAME_LEN and
+ * RewardVault::MAX_METADATA_URI_LEN.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VaultMetadata {
+    pub name: Vec<u8>,          // UTF-8 display name
+    pub metadata_uri: Vec<u8>,  // UTF-8 metadata URI
+}
+
+/**
+ * Sub-Epoch Account
+ * 
+ * One claim shard of an epoch with its own Merkle root. Tracks its own
+ * payouts so claims against different shards do not write to the same
+ * account.
+ */
+#[account]
+pub struct SubEpoch {
+    pub epoch: Pubkey,           // Parent epoch
+    pub shard: u16,              // Shard number within the epoch
+    pub claim_root: [u8; 32],    // Merkle root of this shard's claim allocations
+    pub total_disbursed: u128,   // Total paid out through this shard
+    pub synced_disbursed: u128,  // Portion already added to the epoch's total_disbursed
+    pub bump: u8,                // Bump seed for PDA
+}
+
+impl SubEpoch {
+    pub const SEED: &'static [u8] = b"sub";
+    pub const LEN: usize = 8 + 32 + 2 + 32 + 16 + 16 + 1; // Discriminator + fields
+    pub const MAX_SHARDS: u16 = 16; // All shards must fit in one sweep transaction
+
+    /// Payouts not yet reflected in the parent epoch.
+    pub fn pending_disbursed(&self) -> u128 {
+        self.total_disbursed.saturating_sub(self.synced_disbursed)
+    }
+}
+
+/**
+ * Session Key Account
+ * 
+ * A short-lived key the distributor has authorized to sign disbursements
+ * on its behalf.
+ */
+#[account]
+pub struct SessionKey {
+    pub vault: Pubkey,        // Vault the session applies to
+    pub distributor: Pubkey,  // Distributor that registered the session
+    pub session: Pubkey,      // Key allowed to sign in place of the distributor
+    pub expires_at: i64,      // Last timestamp the session is accepted
+    pub bump: u8,             // Bump seed for PDA
+}
+
+impl SessionKey {
+    pub const SEED: &'static [u8] = b"session";
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 1; // Discriminator + fields
+    pub const MAX_DURATION: i64 = 86_400; // Longest a session may be registered for
+}
+
+/**
+ * Claim Delegate Account
+ * 
+ * Records the guardian a recipient allows to submit claims on their
+ * behalf for a vault.
+ */
+#[account]
+pub struct ClaimDelegate {
+    pub vault: Pubkey,             // Vault the delegation applies to
+    pub recipient: Pubkey,         // Recipient who set the delegate
+    pub delegate: Option<Pubkey>,  // Guardian allowed to claim (None = nobody)
+    pub bump: u8,                  // Bump seed for PDA
+}
+
+impl ClaimDelegate {
+    pub const SEED: &'static [u8] = b"claim_delegate";
+    pub const LEN: usize = 8 + 32 + 32 + 33 + 1; // Discriminator + fields
+}
+
+/**
+ * Acknowledge Terms Account
+ * 
+ * Records that a recipient accepted a specific set of terms before
+ * claiming.
+ */
+#[account]
+pub struct AcknowledgeTerms {
+    pub recipient: Pubkey,     // Recipient who acknowledged
+    pub terms_hash: [u8; 32],  // Hash of the acknowledged terms
+    pub acknowledged_at: i64,  // Timestamp of the acknowledgment
+    pub bump: u8,              // Bump seed for PDA
+}
+
+impl AcknowledgeTerms {
+    pub const SEED: &'static [u8] = b"terms_ack";
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 1; // Discriminator + fields
+}
+
+/**
+ * Vault PDA
+ * 
+ * Return data for derive_vault_pda.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VaultPda {
+    pub address: Pubkey, // Reward vault address for the admin
+    pub bump: u8,        // Canonical bump seed
+}
+
+// ============================================================================
+// EVENTS
+// ============================================================================
+
+/**
+ * New Epoch Event
+ * 
+ * Emitted when a new epoch is started, providing off-chain systems
+ * with epoch information for tracking and processing.
+ */
+#[event]
+pub struct NewEpoch {
+    pub start_ts: i64,      // Epoch start timestamp
+    pub end_ts: i64,        // Epoch end timestamp
+    pub epoch_index: u64,   // Sequential epoch number
+}
+
+/**
+ * Epoch Extended Event
+ * 
+ * Emitted when an epoch's end timestamp is pushed back.
+ */
+#[event]
+pub struct EpochExtended {
+    pub old_end: i64,       // Previous epoch end timestamp
+    pub new_end: i64,       // New epoch end timestamp
+}
+
+/**
+ * Payout Reconfigured Event
+ * 
+ * Emitted when a vault switches payout mode so off-chain systems can
+ * adjust how they interpret amounts.
+ */
+#[event]
+pub struct PayoutReconfigured {
+    pub vault: Pubkey,               // Reconfigured vault account
+    pub pay_sol: bool,               // New payout mode
+    pub reward_mint: Option<Pubkey>, // New SPL token mint (if not SOL)
+}
+
+/**
+ * Payout Mode Changed Event
+ * 
+ * Emitted alongside PayoutReconfigured when a vault switches between SOL
+ * and SPL payouts, so indexers can change how they interpret amounts.
+ */
+#[event]
+pub struct PayoutModeChanged {
+    pub vault: Pubkey,               // Reconfigured vault account
+    pub old_pay_sol: bool,           // Payout mode before the switch
+    pub new_pay_sol: bool,           // Payout mode after the switch
+    pub reward_mint: Option<Pubkey>, // New SPL token mint (if not SOL)
+}
+
+/**
+ * Epoch Swept Event
+ * 
+ * Emitted when an epoch's unspent funds are swept out of the vault.
+ */
+#[event]
+pub struct EpochSwept {
+    pub vault: Pubkey,      // Vault the funds were swept from
+    pub epoch_index: u64,   // Sequential epoch number
+    pub amount: u64,        // Amount swept
+}
+
+/**
+ * Reward Disbursed Event
+ * 
+ * Emitted for each single-recipient disbursement, carrying the optional
+ * compliance memo supplied by the distributor.
+ */
+#[event]
+pub struct RewardDisbursed {
+    pub vault: Pubkey,          // Vault the reward was paid from
+    pub recipient: Pubkey,      // Recipient wallet
+    pub amount: u64,            // Amount disbursed
+    pub memo: Option<String>,   // Optional distributor memo
+    pub vault_balance_after: u64, // Balance of the paying account after the transfer
+}
+
+/**
+ * Funding Cap Reached Event
+ * 
+ * Emitted once per epoch when funding first reaches the epoch's cap.
+ */
+#[event]
+pub struct FundingCapReached {
+    pub vault: Pubkey,          // Vault the epoch belongs to
+    pub epoch_index: u64,       // Sequential epoch number
+    pub total_funded: u128,     // Total funded when the cap was reached
+}
+
+/**
+ * Epoch Cancelled Event
+ * 
+ * Emitted when an epoch with no disbursements is closed by the admin.
+ */
+#[event]
+pub struct EpochCancelled {
+    pub vault: Pubkey,          // Vault the epoch belonged to
+    pub epoch_index: u64,       // Sequential epoch number
+    pub total_funded: u128,     // Funding that had been tracked against the epoch
+}
+
+/**
+ * Reward Refunded Event
+ * 
+ * Emitted when a recipient returns a disbursement to the vault.
+ */
+#[event]
+pub struct RewardRefunded {
+    pub vault: Pubkey,          // Vault receiving the refund
+    pub recipient: Pubkey,      // Recipient returning funds
+    pub epoch_index: u64,       // Epoch the refund is credited against
+    pub amount: u64,            // Amount returned
+}
+
+/**
+ * Reward Escrowed Event
+ * 
+ * Emitted when a reward is moved into escrow pending its challenge period.
+ */
+#[event]
+pub struct RewardEscrowed {
+    pub vault: Pubkey,          // Vault the reward was escrowed from
+    pub recipient: Pubkey,      // Wallet paid on finalization
+    pub amount: u64,            // Escrowed amount
+    pub challenge_until: i64,   // Deadline for an admin challenge
 }
 
-// ============================================================================
-// DATA STRUCTURES
-// ============================================================================
-
 /**
- * Reward Vault Account
+ * Reward Challenged Event
  * 
- * Stores configuration and state for a reward vault including
- * admin authority, distributor authorization, and payment mode.
+ * Emitted when the admin cancels an escrow and its funds return to the vault.
  */
-#[account]
-pub struct RewardVault {
-    pub admin: Pubkey,              // Admin authority for vault management
-    pub distributor: Pubkey,        // Authorized distributor for rewards
-    pub reward_mint: Option<Pubkey>, // SPL token mint (if not SOL)
-    pub pay_sol: bool,              // Whether to pay in SOL or SPL tokens
-    pub bump: u8,                   // Bump seed for PDA
+#[event]
+pub struct RewardChallenged {
+    pub vault: Pubkey,          // Vault the funds returned to
+    pub recipient: Pubkey,      // Recipient whose reward was cancelled
+    pub amount: u64,            // Amount returned
 }
 
-impl RewardVault {
-    pub const SEED: &'static [u8] = b"reward_vault";
-    pub const LEN: usize = 8 + 32 + 32 + 33 + 1 + 1; // Discriminator + fields
+/**
+ * Metadata Updated Event
+ * 
+ * Emitted when a vault's display name or metadata URI changes so
+ * front-ends can refresh their labels.
+ */
+#[event]
+pub struct MetadataUpdated {
+    pub vault: Pubkey,               // Vault that was relabeled
+    pub name: String,                // New display name
+    pub metadata_uri: String,        // New metadata URI
 }
 
 /**
- * Epoch Account
+ * Vault Funded Event
  * 
- * Tracks reward distribution for a specific time period including
- * time boundaries, funding totals, and sequential indexing.
+ * Emitted for every funding with the amount received and the resulting
+ * vault balance, so reconciliation jobs can detect drift.
  */
-#[account]
-pub struct Epoch {
-    pub vault: Pubkey,        // Associated vault account
-    pub start_ts: i64,        // Epoch start timestamp
-    pub end_ts: i64,          // Epoch end timestamp
-    pub index: u64,           // Sequential epoch number
-    pub total_funded: u128,   // Total amount funded for this epoch
-    pub bump: u8,             // Bump seed for PDA
+#[event]
+pub struct VaultFunded {
+    pub vault: Pubkey,          // Vault that was funded
+    pub funder: Pubkey,         // Wallet that funded
+    pub amount: u64,            // Amount the vault received
+    pub vault_balance_after: u64, // Vault balance after the funding
 }
 
-impl Epoch {
-    pub const SEED: &'static [u8] = b"epoch";
-    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 16 + 1; // Discriminator + fields
+/**
+ * Session Revoked Event
+ * 
+ * Emitted when a distributor session key is revoked.
+ */
+#[event]
+pub struct SessionRevoked {
+    pub vault: Pubkey,      // Vault the session applied to
+    pub session: Pubkey,    // Session key that was revoked
+    pub revoked_by: Pubkey, // Distributor or admin that revoked it
 }
 
-// ============================================================================
-// EVENTS
-// ============================================================================
+/**
+ * Epoch Migrated Event
+ * 
+ * Emitted when a legacy epoch moves to its indexed address on the
+ * current layout.
+ */
+#[event]
+pub struct EpochMigrated {
+    pub vault: Pubkey,          // Vault the epoch belongs to
+    pub epoch_index: u64,       // Sequential epoch number
+    pub legacy_address: Pubkey, // Closed `[b"epoch", vault]` account
+    pub address: Pubkey,        // New `[b"epoch", vault, index]` account
+}
 
 /**
- * New Epoch Event
+ * Epoch Reassigned Event
  * 
- * Emitted when a new epoch is started, providing off-chain systems
- * with epoch information for tracking and processing.
+ * Emitted when an epoch and its payout budget move to another vault.
  */
 #[event]
-pub struct NewEpoch {
-    pub start_ts: i64,      // Epoch start timestamp
-    pub end_ts: i64,        // Epoch end timestamp
-    pub epoch_index: u64,   // Sequential epoch number
+pub struct EpochReassigned {
+    pub vault: Pubkey,          // Vault the epoch left
+    pub epoch_index: u64,       // Index of the epoch under the old vault
+    pub new_vault: Pubkey,      // Vault that took the epoch over
+    pub new_epoch_index: u64,   // Index of the epoch under the new vault
+    pub amount: u64,            // Payout budget moved between the vaults
 }
 
 // ============================================================================
@@ -528,4 +9122,1267 @@ pub enum RewardVaultError {
     InsufficientVaultBalance,
     #[msg("Wrong payout mode for this instruction")]
     WrongPayoutMode,
+    #[msg("Recipient is not on the epoch allowlist")]
+    RecipientNotAllowed,
+    #[msg("Batch recipients and amounts must be non-empty and the same length")]
+    BatchLengthMismatch,
+    #[msg("Recipient appears more than once in batch")]
+    DuplicateRecipient,
+    #[msg("Recipient account must be writable")]
+    RecipientNotWritable,
+    #[msg("Recipient must be a system account")]
+    InvalidRecipient,
+    #[msg("Vault still holds funds for its current payout mode")]
+    VaultNotEmpty,
+    #[msg("Vault token account is not the vault's canonical token account")]
+    InvalidVaultTokenAccount,
+    #[msg("Vault already initialized with a different configuration")]
+    VaultAlreadyInitialized,
+    #[msg("Epoch end can only be extended, not shortened")]
+    CannotShortenEpoch,
+    #[msg("Epoch has already ended")]
+    EpochAlreadyEnded,
+    #[msg("Amount exceeds the maximum single funding")]
+    AmountTooLarge,
+    #[msg("Epoch has not ended yet")]
+    EpochNotEnded,
+    #[msg("Stake snapshot has zero total weight")]
+    ZeroTotalWeight,
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+    #[msg("Recipient token account required")]
+    RecipientTokenRequired,
+    #[msg("Token account owner does not match recipient")]
+    RecipientMismatch,
+    #[msg("Claim grace period must not be negative")]
+    InvalidGracePeriod,
+    #[msg("Epoch claim window is still open")]
+    ClaimWindowOpen,
+    #[msg("Epoch has already been swept")]
+    EpochAlreadySwept,
+    #[msg("Rollover source and target epochs must differ")]
+    InvalidRolloverTarget,
+    #[msg("Epoch has already been rolled over")]
+    EpochAlreadyRolledOver,
+    #[msg("Voucher has expired")]
+    VoucherExpired,
+    #[msg("Voucher signature is missing or invalid")]
+    InvalidVoucherSignature,
+    #[msg("Amount is below the minimum disbursement")]
+    BelowMinimum,
+    #[msg("Funder is not on the vault funder allowlist")]
+    FunderNotAllowed,
+    #[msg("Vault has reached its maximum number of epochs")]
+    MaxEpochsReached,
+    #[msg("Memo exceeds the maximum length")]
+    MemoTooLong,
+    #[msg("Disbursement cooldown must not be negative")]
+    InvalidCooldown,
+    #[msg("Distributor cooldown is still active")]
+    CooldownActive,
+    #[msg("Funding leaves the vault below its rent-exempt minimum")]
+    InsufficientInitialFunding,
+    #[msg("Invalid claim proof")]
+    InvalidClaimProof,
+    #[msg("Claim account does not match the expected PDA")]
+    InvalidClaimAccount,
+    #[msg("Epoch has already been claimed")]
+    AlreadyClaimed,
+    #[msg("Treasury account does not match the vault treasury")]
+    InvalidTreasury,
+    #[msg("Illegal epoch status transition")]
+    InvalidEpochStatus,
+    #[msg("Epoch is not active")]
+    EpochNotActive,
+    #[msg("Epoch has already made disbursements")]
+    EpochHasDisbursements,
+    #[msg("Recipient was paid too recently")]
+    RecipientCooldownActive,
+    #[msg("Vault reward mint is not wrapped SOL")]
+    NotWrappedSol,
+    #[msg("Vault configuration hash does not match")]
+    ConfigHashMismatch,
+    #[msg("Epoch account required for this operation")]
+    EpochRequired,
+    #[msg("Batch exceeds the maximum size")]
+    BatchTooLarge,
+    #[msg("Epoch indices must be sequential")]
+    NonSequentialEpochIndex,
+    #[msg("Epoch window overlaps a previous epoch")]
+    OverlappingEpoch,
+    #[msg("Epoch has no fixed payouts remaining")]
+    EpochFullyPaid,
+    #[msg("Vault token account is frozen")]
+    VaultTokenFrozen,
+    #[msg("Recipient token account is frozen")]
+    RecipientTokenFrozen,
+    #[msg("Challenge deadline must be in the future")]
+    InvalidChallengeDeadline,
+    #[msg("Challenge period has ended")]
+    ChallengePeriodEnded,
+    #[msg("Challenge period is still active")]
+    ChallengePeriodActive,
+    #[msg("Funder contribution account does not match")]
+    InvalidContributionAccount,
+    #[msg("Amount exceeds the funder's contribution")]
+    ContributionExceeded,
+    #[msg("Epoch has already started")]
+    EpochAlreadyStarted,
+    #[msg("Account does not have a recognized layout")]
+    InvalidAccountLayout,
+    #[msg("Account is already on the current layout")]
+    AlreadyMigrated,
+    #[msg("Signer is not the vault admin")]
+    UnauthorizedAdmin,
+    #[msg("Vault is paused")]
+    VaultPaused,
+    #[msg("Signer is neither the vault admin nor its operator")]
+    UnauthorizedOperator,
+    #[msg("Recipient registry is full")]
+    RegistryFull,
+    #[msg("Registry index out of bounds")]
+    InvalidRegistryIndex,
+    #[msg("Reward mint is not an initialized SPL token mint")]
+    InvalidRewardMint,
+    #[msg("Recipient is blocked")]
+    RecipientBlocked,
+    #[msg("Vault blocklist account required")]
+    BlocklistRequired,
+    #[msg("Blocklist is full")]
+    BlocklistFull,
+    #[msg("Disbursement requires the admin's co-signature")]
+    MissingAdminCosignature,
+    #[msg("Payout rate must be greater than zero")]
+    InvalidPayoutRate,
+    #[msg("Disbursement exceeds the epoch's payout budget")]
+    EpochBudgetExceeded,
+    #[msg("Relayer fee exceeds the allowed maximum")]
+    RelayerFeeTooHigh,
+    #[msg("Amount does not match any configured reward tier")]
+    InvalidRewardTier,
+    #[msg("Too many reward tiers")]
+    TooManyRewardTiers,
+    #[msg("Vault does not pay out in SPL tokens")]
+    NotSplVault,
+    #[msg("Staked total must be greater than zero")]
+    ZeroStakedTotal,
+    #[msg("Fund receipt account required for an idempotency key")]
+    FundReceiptRequired,
+    #[msg("Fund receipt account does not match the expected PDA")]
+    InvalidFundReceipt,
+    #[msg("Vault audit log account required")]
+    AuditLogRequired,
+    #[msg("Payout would take the vault below its SOL reserve floor")]
+    ReserveFloorHit,
+    #[msg("Claim deadline has passed")]
+    ClaimWindowClosed,
+    #[msg("Claim deadline cannot be negative")]
+    InvalidClaimDeadline,
+    #[msg("Funder token account is not owned by the funder")]
+    FunderTokenOwnerMismatch,
+    #[msg("Destination account required to pay a different wallet")]
+    DestinationRequired,
+    #[msg("Vault stats account required")]
+    VaultStatsRequired,
+    #[msg("Epoch has not received any funding")]
+    EpochNotFunded,
+    #[msg("Vault metadata exceeds its maximum length")]
+    MetadataTooLong,
+    #[msg("Vault metadata is not valid UTF-8")]
+    InvalidMetadata,
+    #[msg("Sub-epoch does not belong to this epoch or is out of order")]
+    InvalidSubEpoch,
+    #[msg("Epoch already has the maximum number of sub-epochs")]
+    TooManySubEpochs,
+    #[msg("Every sub-epoch of the epoch must be supplied exactly once")]
+    SubEpochsRequired,
+    #[msg("Session key has expired")]
+    SessionExpired,
+    #[msg("Session key does not belong to this vault")]
+    InvalidSessionKey,
+    #[msg("Session expiry must be in the future and within the maximum duration")]
+    InvalidSessionExpiry,
+    #[msg("Merkle proof is longer than the epoch's depth cap")]
+    ProofTooDeep,
+    #[msg("Proof depth cap must be between 1 and 32")]
+    InvalidProofDepth,
+    #[msg("Reward multiplier must be greater than zero")]
+    InvalidRewardMultiplier,
+    #[msg("Boosted payout exceeds the epoch's remaining funds")]
+    MultiplierExceedsBudget,
+    #[msg("Epoch must move to the supplied, different vault")]
+    InvalidReassignTarget,
+    #[msg("Signer is not the recipient's claim delegate")]
+    UnauthorizedDelegate,
+    #[msg("Funding would exceed the epoch's hard cap")]
+    FundingCapExceeded,
+    #[msg("Funding commitment is missing or does not match the reveal")]
+    InvalidFundingCommitment,
+    #[msg("Epoch funding commitment has not been revealed")]
+    FundingNotRevealed,
+    #[msg("Vault still has open escrows")]
+    OutstandingObligations,
+    #[msg("Protocol fee exceeds the allowed maximum")]
+    InvalidFeeBps,
+    #[msg("Recipient is not on the fee-exempt list")]
+    InvalidFeeExemption,
+    #[msg("Treasury account required to collect the protocol fee")]
+    TreasuryRequired,
+    #[msg("Vault has no epoch cadence configured")]
+    EpochCadenceNotSet,
+    #[msg("Epoch funding is finalized")]
+    FundingLocked,
+    #[msg("Payout exceeds the vault's maximum disbursement fraction")]
+    ExceedsMaxDisburseFraction,
+    #[msg("Maximum disbursement fraction must be between 1 and 10000 bps")]
+    InvalidMaxDisburseBps,
+    #[msg("Recipient has not acknowledged the epoch's terms")]
+    TermsNotAcknowledged,
+    #[msg("Vault requires a memo on every payout")]
+    MemoRequired,
+    #[msg("Claim amount exceeds the unclaimed allocation")]
+    ClaimExceedsAllocation,
+    #[msg("Epoch has sub-epochs")]
+    EpochHasSubEpochs,
+    #[msg("Epoch has not been cancelled")]
+    EpochNotCancelled,
+    #[msg("Both vaults must pay out the same asset")]
+    ReassignAssetMismatch,
+    #[msg("Recipient stats account does not match the expected PDA")]
+    InvalidRecipientStats,
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deserialize an account type from zeroed bytes: every field is zero,
+    /// false, None, empty, or its first variant.
+    fn zeroed<T: AnchorDeserialize>(len: usize) -> T {
+        T::deserialize(&mut &vec![0u8; len - 8][..]).unwrap()
+    }
+
+    /// Leak an account for the lifetime of the test process.
+    fn leak_account(key: Pubkey, owner: Pubkey, is_signer: bool, data: Vec<u8>) -> &'static AccountInfo<'static> {
+        Box::leak(Box::new(AccountInfo::new(
+            Box::leak(Box::new(key)),
+            is_signer,
+            false,
+            Box::leak(Box::new(0u64)),
+            Box::leak(data.into_boxed_slice()),
+            Box::leak(Box::new(owner)),
+            false,
+            0,
+        )))
+    }
+
+    /// Serialize a program account so it can be loaded through `Account`.
+    fn program_account<T: AccountSerialize>(key: Pubkey, value: &T, len: usize) -> &'static AccountInfo<'static> {
+        let mut data = Vec::with_capacity(len);
+        value.try_serialize(&mut data).unwrap();
+        data.resize(len, 0);
+        leak_account(key, crate::ID, false, data)
+    }
+
+    fn test_vault(configure: impl FnOnce(&mut RewardVault)) -> Account<'static, RewardVault> {
+        let mut reward_vault: RewardVault = zeroed(RewardVault::LEN);
+        reward_vault.admin = Pubkey::new_unique();
+        configure(&mut reward_vault);
+
+        let info = program_account(Pubkey::new_unique(), &reward_vault, RewardVault::LEN);
+        Account::try_from(info).unwrap()
+    }
+
+    /// An executable account for a program id, loadable as `Program`.
+    fn executable_account(id: Pubkey) -> &'static AccountInfo<'static> {
+        Box::leak(Box::new(AccountInfo::new(
+            Box::leak(Box::new(id)),
+            false,
+            false,
+            Box::leak(Box::new(0u64)),
+            Box::leak(Vec::new().into_boxed_slice()),
+            Box::leak(Box::new(Pubkey::default())),
+            true,
+            0,
+        )))
+    }
+
+    fn token_program() -> Program<'static, Token> {
+        Program::try_from(executable_account(Token::id())).unwrap()
+    }
+
+    fn system_program() -> Program<'static, System> {
+        Program::try_from(executable_account(System::id())).unwrap()
+    }
+
+    /// Unix time the stubbed Clock sysvar reports to every test.
+    const NOW: i64 = 1_700_000_000;
+
+    struct TestSyscalls;
+
+    impl anchor_lang::solana_program::program_stubs::SyscallStubs for TestSyscalls {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            let clock = Clock {
+                unix_timestamp: NOW,
+                ..Clock::default()
+            };
+            unsafe { *(var_addr as *mut Clock) = clock };
+            anchor_lang::solana_program::entrypoint::SUCCESS
+        }
+
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe { *(var_addr as *mut Rent) = Rent::default() };
+            anchor_lang::solana_program::entrypoint::SUCCESS
+        }
+
+        /// Run Token-2022 `transfer_checked` as a mint with a 1% transfer fee
+        /// would, withholding the fee from what the destination receives.
+        fn sol_invoke_signed(
+            &self,
+            instruction: &anchor_lang::solana_program::instruction::Instruction,
+            account_infos: &[AccountInfo],
+            _signers_seeds: &[&[&[u8]]],
+        ) -> anchor_lang::solana_program::entrypoint::ProgramResult {
+            use anchor_spl::token_2022::spl_token_2022::{instruction::TokenInstruction, state::Account as TokenState};
+
+            let Ok(TokenInstruction::TransferChecked { amount, .. }) = TokenInstruction::unpack(&instruction.data) else {
+                return Err(ProgramError::InvalidInstructionData);
+            };
+            if instruction.program_id != anchor_spl::token_2022::ID {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+
+            let account = |index: usize| {
+                account_infos
+                    .iter()
+                    .find(|info| *info.key == instruction.accounts[index].pubkey)
+                    .ok_or(ProgramError::NotEnoughAccountKeys)
+            };
+            let fee = amount / 100;
+            for (info, debit, credit) in [(account(0)?, amount, 0), (account(2)?, 0, amount - fee)] {
+                let mut state = TokenState::unpack(&info.try_borrow_data()?)?;
+                state.amount = state.amount - debit + credit;
+                TokenState::pack(state, &mut info.try_borrow_mut_data()?)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Install the stubbed sysvars once per test process.
+    fn stub_syscalls() {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            anchor_lang::solana_program::program_stubs::set_syscall_stubs(Box::new(TestSyscalls));
+        });
+    }
+
+    /// An epoch of `vault` open from `start_ts` until `end_ts`.
+    fn test_epoch(vault: Pubkey, start_ts: i64, end_ts: i64) -> Account<'static, Epoch> {
+        let mut epoch: Epoch = zeroed(Epoch::LEN);
+        epoch.vault = vault;
+        epoch.start_ts = start_ts;
+        epoch.end_ts = end_ts;
+
+        let info = program_account(Pubkey::new_unique(), &epoch, Epoch::LEN);
+        Account::try_from(info).unwrap()
+    }
+
+    fn allowlist_leaf(key: &Pubkey) -> [u8; 32] {
+        keccak::hashv(&[key.as_ref()]).0
+    }
+
+    fn sorted_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        if a <= b {
+            keccak::hashv(&[&a, &b]).0
+        } else {
+            keccak::hashv(&[&b, &a]).0
+        }
+    }
+
+    #[test]
+    fn allowlist_admits_members_only_unless_open() {
+        let member = Pubkey::new_unique();
+        let other_member = Pubkey::new_unique();
+        let outsider = Pubkey::new_unique();
+        let root = sorted_pair(allowlist_leaf(&member), allowlist_leaf(&other_member));
+
+        assert!(is_allowlisted(root, member, &[allowlist_leaf(&other_member)]));
+        assert!(!is_allowlisted(root, outsider, &[allowlist_leaf(&other_member)]));
+        assert!(!is_allowlisted(root, member, &[]));
+
+        // A zeroed root leaves the epoch open to anyone
+        assert!(is_allowlisted([0u8; 32], outsider, &[]));
+    }
+
+    #[test]
+    fn blocked_recipient_is_rejected_and_others_pass() {
+        let blocked = Pubkey::new_unique();
+        let allowed = Pubkey::new_unique();
+        let reward_vault = test_vault(|vault| vault.blocklist_active = true);
+
+        let blocklist = Blocklist {
+            vault: reward_vault.key(),
+            entries: vec![blocked],
+            bump: 0,
+        };
+        let blocklist_info = program_account(Pubkey::new_unique(), &blocklist, Blocklist::LEN);
+        let blocklist = Account::<Blocklist>::try_from(blocklist_info).unwrap();
+
+        assert_eq!(
+            check_not_blocked(&reward_vault, Some(&blocklist), blocked).unwrap_err(),
+            RewardVaultError::RecipientBlocked.into()
+        );
+        assert!(check_not_blocked(&reward_vault, Some(&blocklist), allowed).is_ok());
+        assert_eq!(
+            check_not_blocked(&reward_vault, None, allowed).unwrap_err(),
+            RewardVaultError::BlocklistRequired.into()
+        );
+    }
+
+
+    #[test]
+    fn block_recipient_adds_each_address_once() {
+        let reward_vault = test_vault(|_| {});
+        let blocked = Pubkey::new_unique();
+
+        let authority = leak_account(reward_vault.admin, System::id(), true, vec![]);
+        let blocklist: Blocklist = zeroed(Blocklist::LEN);
+        let mut accounts = UpdateBlocklist {
+            authority: Signer::try_from(authority).unwrap(),
+            reward_vault,
+            blocklist: Account::try_from(program_account(Pubkey::new_unique(), &blocklist, Blocklist::LEN)).unwrap(),
+            system_program: system_program(),
+        };
+
+        for _ in 0..2 {
+            let ctx = Context::new(&crate::ID, &mut accounts, &[], UpdateBlocklistBumps { blocklist: 254 });
+            reward_vault::block_recipient(ctx, blocked).unwrap();
+        }
+        assert_eq!(accounts.blocklist.entries, vec![blocked]);
+        assert_eq!(accounts.blocklist.vault, accounts.reward_vault.key());
+        assert!(accounts.reward_vault.blocklist_active);
+
+        let ctx = Context::new(&crate::ID, &mut accounts, &[], UpdateBlocklistBumps { blocklist: 254 });
+        reward_vault::unblock_recipient(ctx, blocked).unwrap();
+        assert!(accounts.blocklist.entries.is_empty());
+        assert!(!accounts.reward_vault.blocklist_active);
+    }
+
+
+    #[test]
+    fn admin_cosign_requires_the_admin_signature() {
+        let reward_vault = test_vault(|vault| vault.require_admin_cosign = true);
+        let admin = reward_vault.admin;
+
+        let signed = UncheckedAccount::try_from(leak_account(admin, System::id(), true, vec![]));
+        assert!(check_admin_cosign(&reward_vault, Some(&signed)).is_ok());
+
+        let unsigned = UncheckedAccount::try_from(leak_account(admin, System::id(), false, vec![]));
+        assert_eq!(
+            check_admin_cosign(&reward_vault, Some(&unsigned)).unwrap_err(),
+            RewardVaultError::MissingAdminCosignature.into()
+        );
+        assert_eq!(
+            check_admin_cosign(&reward_vault, None).unwrap_err(),
+            RewardVaultError::MissingAdminCosignature.into()
+        );
+
+        // Single-signer vaults need no co-signature at all
+        let single_signer = test_vault(|_| {});
+        assert!(check_admin_cosign(&single_signer, None).is_ok());
+    }
+
+
+    #[test]
+    fn payout_budget_rounds_down_at_the_rate() {
+        let mut epoch: Epoch = zeroed(Epoch::LEN);
+        epoch.total_funded = 10;
+        assert_eq!(epoch.payout_budget().unwrap(), 10);
+
+        // 10 * 1/3 = 3.33.. rounds down to 3
+        epoch.payout_rate = Some(333_333_333);
+        assert_eq!(epoch.payout_budget().unwrap(), 3);
+
+        epoch.payout_rate = Some(2 * Epoch::RATE_SCALE as u64);
+        assert_eq!(epoch.payout_budget().unwrap(), 20);
+    }
+
+
+    #[test]
+    fn derive_vault_pda_matches_find_program_address() {
+        let admin = Pubkey::new_unique();
+        let mut accounts = DeriveVaultPda {};
+        let ctx = Context::new(&crate::ID, &mut accounts, &[], DeriveVaultPdaBumps::default());
+
+        let derived = reward_vault::derive_vault_pda(ctx, admin).unwrap();
+        let (address, bump) = Pubkey::find_program_address(&[b"reward_vault", admin.as_ref()], &crate::ID);
+        assert_eq!(derived.address, address);
+        assert_eq!(derived.bump, bump);
+    }
+
+
+    #[test]
+    fn audit_log_overwrites_the_oldest_entries() {
+        let mut audit_log: AuditLog = zeroed(AuditLog::LEN);
+        let recipient = Pubkey::new_unique();
+        let total = AuditLog::CAPACITY as u64 + 2;
+        for amount in 0..total {
+            audit_log.record(recipient, amount, amount as i64);
+        }
+
+        let recent = audit_log.recent();
+        assert_eq!(recent.len(), AuditLog::CAPACITY);
+        assert_eq!(recent[0].amount, total - 1);
+        assert_eq!(recent[AuditLog::CAPACITY - 1].amount, 2);
+    }
+
+
+    #[test]
+    fn vault_stats_accumulate_counters() {
+        let mut vault_stats: VaultStats = zeroed(VaultStats::LEN);
+        vault_stats.record_epochs(2).unwrap();
+        vault_stats.record_funding(500).unwrap();
+        vault_stats.record_disbursements(120, 3).unwrap();
+        vault_stats.record_disbursements(30, 1).unwrap();
+
+        assert_eq!(vault_stats.total_epochs_created, 2);
+        assert_eq!(vault_stats.total_funded_all_time, 500);
+        assert_eq!(vault_stats.total_disbursed_all_time, 150);
+        assert_eq!(vault_stats.disbursement_count, 4);
+
+        vault_stats.disbursement_count = u64::MAX;
+        assert!(vault_stats.record_disbursements(1, 1).is_err());
+    }
+
+
+    #[test]
+    fn partial_claims_stop_at_the_allocation() {
+        let mut claim: Claim = zeroed(Claim::LEN);
+        claim.amount = 100;
+
+        claim.claimed = check_claim_amount(&claim, 100, 40).unwrap();
+        assert_eq!(claim.claimed, 40);
+        assert_eq!(claim.remaining(), 60);
+
+        assert_eq!(
+            check_claim_amount(&claim, 100, 61).unwrap_err(),
+            RewardVaultError::ClaimExceedsAllocation.into()
+        );
+
+        claim.claimed = check_claim_amount(&claim, 100, 60).unwrap();
+        assert_eq!(claim.claimed, 100);
+        assert_eq!(claim.remaining(), 0);
+
+        assert_eq!(
+            check_claim_amount(&claim, 100, 1).unwrap_err(),
+            RewardVaultError::AlreadyClaimed.into()
+        );
+    }
+
+
+    #[test]
+    fn epoch_status_follows_the_window_unless_overridden() {
+        let mut epoch: Epoch = zeroed(Epoch::LEN);
+        epoch.start_ts = 100;
+        epoch.end_ts = 200;
+
+        assert_eq!(epoch.effective_status(99), EpochStatus::Pending);
+        assert_eq!(epoch.effective_status(100), EpochStatus::Active);
+        assert_eq!(epoch.effective_status(200), EpochStatus::Ended);
+
+        // activate_epoch opens early, but the window still closes it
+        epoch.status = EpochStatus::Active;
+        assert_eq!(epoch.effective_status(50), EpochStatus::Active);
+        assert_eq!(epoch.effective_status(200), EpochStatus::Ended);
+
+        // finalize_epoch ends it early
+        epoch.status = EpochStatus::Ended;
+        assert_eq!(epoch.effective_status(150), EpochStatus::Ended);
+
+        epoch.status = EpochStatus::Swept;
+        assert_eq!(epoch.effective_status(150), EpochStatus::Swept);
+    }
+
+    #[test]
+    fn epoch_transitions_reject_the_wrong_status() {
+        stub_syscalls();
+        let reward_vault = test_vault(|_| {});
+        let admin = Signer::try_from(leak_account(reward_vault.admin, System::id(), true, vec![])).unwrap();
+        let epoch = test_epoch(reward_vault.key(), NOW + 100, NOW + 200);
+        let mut accounts = UpdateEpoch {
+            admin,
+            reward_vault,
+            epoch,
+        };
+
+        // Pending -> Active early, then Active -> Ended
+        reward_vault::activate_epoch(Context::new(&crate::ID, &mut accounts, &[], UpdateEpochBumps {})).unwrap();
+        assert_eq!(accounts.epoch.status, EpochStatus::Active);
+        assert_eq!(
+            reward_vault::activate_epoch(Context::new(&crate::ID, &mut accounts, &[], UpdateEpochBumps {})).unwrap_err(),
+            RewardVaultError::InvalidEpochStatus.into()
+        );
+        reward_vault::finalize_epoch(Context::new(&crate::ID, &mut accounts, &[], UpdateEpochBumps {})).unwrap();
+        assert_eq!(accounts.epoch.status, EpochStatus::Ended);
+        assert_eq!(
+            reward_vault::finalize_epoch(Context::new(&crate::ID, &mut accounts, &[], UpdateEpochBumps {})).unwrap_err(),
+            RewardVaultError::InvalidEpochStatus.into()
+        );
+        assert_eq!(
+            reward_vault::close_epoch(Context::new(&crate::ID, &mut accounts, &[], UpdateEpochBumps {})).unwrap_err(),
+            RewardVaultError::InvalidEpochStatus.into()
+        );
+    }
+
+    #[test]
+    fn close_epoch_requires_the_window_to_elapse() {
+        stub_syscalls();
+        let reward_vault = test_vault(|_| {});
+        let admin = Signer::try_from(leak_account(reward_vault.admin, System::id(), true, vec![])).unwrap();
+        let epoch = test_epoch(reward_vault.key(), NOW - 200, NOW + 100);
+        let mut accounts = UpdateEpoch {
+            admin,
+            reward_vault,
+            epoch,
+        };
+
+        // Already Active from its start_ts without activate_epoch
+        assert!(check_epoch_active(Some(&accounts.epoch)).is_ok());
+        assert_eq!(
+            reward_vault::close_epoch(Context::new(&crate::ID, &mut accounts, &[], UpdateEpochBumps {})).unwrap_err(),
+            RewardVaultError::EpochNotEnded.into()
+        );
+
+        accounts.epoch.end_ts = NOW;
+        assert_eq!(
+            check_epoch_active(Some(&accounts.epoch)).unwrap_err(),
+            RewardVaultError::EpochNotActive.into()
+        );
+        reward_vault::close_epoch(Context::new(&crate::ID, &mut accounts, &[], UpdateEpochBumps {})).unwrap();
+        assert_eq!(accounts.epoch.status, EpochStatus::Ended);
+    }
+
+
+    #[test]
+    fn legacy_epoch_address_differs_from_the_indexed_one() {
+        let vault = Pubkey::new_unique();
+        let (legacy, _) = Epoch::find_legacy_address(&vault);
+        assert_eq!(legacy, Pubkey::find_program_address(&[b"epoch", vault.as_ref()], &crate::ID).0);
+        assert_ne!(legacy, Epoch::find_address(&vault, 0).0);
+    }
+
+    #[test]
+    fn rollover_moves_the_remainder_once() {
+        stub_syscalls();
+        let reward_vault = test_vault(|_| {});
+        let admin = Signer::try_from(leak_account(reward_vault.admin, System::id(), true, vec![])).unwrap();
+        let mut from_epoch = test_epoch(reward_vault.key(), NOW - 200, NOW - 100);
+        from_epoch.total_funded = 500;
+        from_epoch.total_disbursed = 120;
+        let mut to_epoch = test_epoch(reward_vault.key(), NOW - 100, NOW + 100);
+        to_epoch.total_funded = 50;
+        let mut accounts = RolloverEpoch {
+            admin,
+            reward_vault,
+            from_epoch,
+            to_epoch,
+        };
+
+        // Ended by its window alone, without finalize_epoch
+        reward_vault::rollover_epoch(Context::new(&crate::ID, &mut accounts, &[], RolloverEpochBumps {}), 0, 1).unwrap();
+        assert_eq!(accounts.to_epoch.total_funded, 430);
+        assert!(accounts.from_epoch.rolled_over);
+
+        assert_eq!(
+            reward_vault::rollover_epoch(Context::new(&crate::ID, &mut accounts, &[], RolloverEpochBumps {}), 0, 1).unwrap_err(),
+            RewardVaultError::EpochAlreadyRolledOver.into()
+        );
+        assert_eq!(accounts.to_epoch.total_funded, 430);
+    }
+
+
+    #[test]
+    fn legacy_epoch_migrates_to_the_current_layout() {
+        let vault = Pubkey::new_unique();
+        let legacy = EpochV0 {
+            vault,
+            start_ts: 100,
+            end_ts: 200,
+            index: 7,
+            total_funded: 5_000,
+            bump: Epoch::find_legacy_address(&vault).1,
+        };
+        let mut data = Epoch::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), EpochV0::LEN);
+
+        let (_, bump) = Epoch::find_address(&vault, 7);
+        let epoch = EpochV0::deserialize(&mut &data[8..]).unwrap().migrate(bump);
+        assert_eq!(epoch.vault, vault);
+        assert_eq!((epoch.start_ts, epoch.end_ts, epoch.index), (100, 200, 7));
+        assert_eq!(epoch.total_funded, 5_000);
+        assert_eq!(epoch.total_disbursed, 0);
+        assert_eq!(epoch.status, EpochStatus::Pending);
+        assert_eq!(epoch.effective_status(150), EpochStatus::Active);
+        assert_eq!(epoch.version, Epoch::VERSION);
+        assert_eq!(epoch.bump, bump);
+
+        let mut migrated = Vec::new();
+        epoch.try_serialize(&mut migrated).unwrap();
+        assert!(migrated.len() <= Epoch::LEN);
+    }
+
+
+    #[test]
+    fn cancel_epoch_requires_no_payouts_or_sub_epochs() {
+        let reward_vault = test_vault(|_| {});
+        let admin = Signer::try_from(leak_account(reward_vault.admin, System::id(), true, vec![])).unwrap();
+        let epoch = test_epoch(reward_vault.key(), NOW, NOW + 100);
+        let mut accounts = CancelEpoch {
+            admin,
+            reward_vault,
+            epoch,
+        };
+        assert!(reward_vault::cancel_epoch(Context::new(&crate::ID, &mut accounts, &[], CancelEpochBumps {})).is_ok());
+
+        accounts.epoch.sub_epoch_count = 2;
+        assert_eq!(
+            reward_vault::cancel_epoch(Context::new(&crate::ID, &mut accounts, &[], CancelEpochBumps {})).unwrap_err(),
+            RewardVaultError::EpochHasSubEpochs.into()
+        );
+
+        accounts.epoch.total_disbursed = 1;
+        assert_eq!(
+            reward_vault::cancel_epoch(Context::new(&crate::ID, &mut accounts, &[], CancelEpochBumps {})).unwrap_err(),
+            RewardVaultError::EpochHasDisbursements.into()
+        );
+    }
+
+    #[test]
+    fn reclaim_contribution_refunds_only_cancelled_epochs() {
+        stub_syscalls();
+        let reward_vault = test_vault(|vault| vault.pay_sol = true);
+        let rent_minimum = Rent::default().minimum_balance(RewardVault::LEN);
+        **reward_vault.to_account_info().try_borrow_mut_lamports().unwrap() = rent_minimum + 1_000;
+
+        let funder_info = leak_account(Pubkey::new_unique(), System::id(), true, vec![]);
+        let (epoch_key, _) = Epoch::find_address(&reward_vault.key(), 3);
+        let contribution = FunderContribution {
+            epoch: epoch_key,
+            funder: funder_info.key(),
+            amount: 400,
+            bump: 0,
+        };
+        let contribution_info = program_account(Pubkey::new_unique(), &contribution, FunderContribution::LEN);
+
+        let live_epoch: Epoch = zeroed(Epoch::LEN);
+        let mut accounts = ReclaimContribution {
+            funder: Signer::try_from(funder_info).unwrap(),
+            reward_vault,
+            epoch: UncheckedAccount::try_from(program_account(epoch_key, &live_epoch, Epoch::LEN)),
+            funder_contribution: Account::try_from(contribution_info).unwrap(),
+            vault_token_account: None,
+            funder_token_account: None,
+            token_program: token_program(),
+            vault_stats: None,
+        };
+        assert_eq!(
+            reward_vault::reclaim_contribution(Context::new(&crate::ID, &mut accounts, &[], ReclaimContributionBumps { epoch: 255 }), 3).unwrap_err(),
+            RewardVaultError::EpochNotCancelled.into()
+        );
+
+        accounts.epoch = UncheckedAccount::try_from(leak_account(epoch_key, System::id(), false, vec![]));
+        reward_vault::reclaim_contribution(Context::new(&crate::ID, &mut accounts, &[], ReclaimContributionBumps { epoch: 255 }), 3).unwrap();
+        assert_eq!(accounts.funder.lamports(), 400);
+        assert_eq!(accounts.reward_vault.to_account_info().lamports(), rent_minimum + 600);
+        assert_eq!(accounts.funder_contribution.amount, 0);
+    }
+
+
+    #[test]
+    fn reassign_epoch_moves_the_budget_to_the_new_vault() {
+        stub_syscalls();
+        let rent_minimum = Rent::default().minimum_balance(RewardVault::LEN);
+        let reward_vault = test_vault(|vault| vault.pay_sol = true);
+        **reward_vault.to_account_info().try_borrow_mut_lamports().unwrap() = rent_minimum + 1_000;
+        let new_vault = test_vault(|vault| vault.pay_sol = true);
+        **new_vault.to_account_info().try_borrow_mut_lamports().unwrap() = rent_minimum;
+
+        let admin = Signer::try_from(leak_account(reward_vault.admin, System::id(), true, vec![])).unwrap();
+        let new_admin = Signer::try_from(leak_account(new_vault.admin, System::id(), true, vec![])).unwrap();
+        let mut epoch = test_epoch(reward_vault.key(), NOW, NOW + 100);
+        epoch.index = 4;
+        epoch.total_funded = 700;
+        let new_vault_key = new_vault.key();
+        let new_epoch = test_epoch(Pubkey::default(), 0, 0);
+        let mut accounts = ReassignEpoch {
+            admin,
+            reward_vault,
+            new_admin,
+            new_vault,
+            epoch,
+            new_epoch,
+            vault_token_account: None,
+            new_vault_token_account: None,
+            token_program: token_program(),
+            system_program: system_program(),
+        };
+
+        accounts.epoch.total_disbursed = 1;
+        assert_eq!(
+            reward_vault::reassign_epoch(Context::new(&crate::ID, &mut accounts, &[], ReassignEpochBumps { new_epoch: 253 }), new_vault_key, 0).unwrap_err(),
+            RewardVaultError::EpochHasDisbursements.into()
+        );
+
+        accounts.epoch.total_disbursed = 0;
+        reward_vault::reassign_epoch(Context::new(&crate::ID, &mut accounts, &[], ReassignEpochBumps { new_epoch: 253 }), new_vault_key, 0).unwrap();
+        assert_eq!(accounts.new_epoch.vault, new_vault_key);
+        assert_eq!(accounts.new_epoch.index, 0);
+        assert_eq!(accounts.new_epoch.total_funded, 700);
+        assert_eq!(accounts.new_epoch.bump, 253);
+        assert!(accounts.epoch.rolled_over);
+        assert_eq!(accounts.reward_vault.to_account_info().lamports(), rent_minimum + 300);
+        assert_eq!(accounts.new_vault.to_account_info().lamports(), rent_minimum + 700);
+        assert_eq!(accounts.new_vault.last_epoch_end, NOW + 100);
+    }
+
+
+    #[test]
+    fn sub_epoch_claims_are_tracked_per_shard() {
+        stub_syscalls();
+        let rent_minimum = Rent::default().minimum_balance(RewardVault::LEN);
+        let reward_vault = test_vault(|vault| vault.pay_sol = true);
+        **reward_vault.to_account_info().try_borrow_mut_lamports().unwrap() = rent_minimum + 1_000;
+        let epoch = test_epoch(reward_vault.key(), NOW - 100, NOW + 100);
+        let recipient_info = leak_account(Pubkey::new_unique(), System::id(), true, vec![]);
+        let recipient = recipient_info.key();
+
+        // A one-leaf tree per shard: the root is the leaf itself
+        let root = claim_leaf(epoch.index, recipient, 300);
+        let epoch_key = epoch.key();
+        let shard_claim = |shard: u16| {
+            let seeds = [Claim::SEED, epoch_key.as_ref(), recipient.as_ref(), &shard.to_le_bytes()];
+            Pubkey::find_program_address(&seeds, &crate::ID).0
+        };
+        assert_ne!(shard_claim(0), shard_claim(1));
+
+        for shard in 0..2u16 {
+            let sub_epoch = SubEpoch {
+                epoch: epoch_key,
+                shard,
+                claim_root: root,
+                total_disbursed: 0,
+                synced_disbursed: 0,
+                bump: 0,
+            };
+            let claim: Claim = zeroed(Claim::LEN);
+            let mut accounts = ClaimSubEpoch {
+                recipient: Signer::try_from(recipient_info).unwrap(),
+                reward_vault: reward_vault.clone(),
+                epoch: epoch.clone(),
+                sub_epoch: Account::try_from(program_account(Pubkey::new_unique(), &sub_epoch, SubEpoch::LEN)).unwrap(),
+                claim: Account::try_from(program_account(shard_claim(shard), &claim, Claim::LEN)).unwrap(),
+                vault_token_account: None,
+                recipient_token_account: None,
+                token_program: token_program(),
+                system_program: system_program(),
+                acknowledgment: None,
+                blocklist: None,
+                audit_log: None,
+                vault_stats: None,
+            };
+
+            // Each shard's allocation is claimable in full, in parts
+            reward_vault::claim_sub_epoch(Context::new(&crate::ID, &mut accounts, &[], ClaimSubEpochBumps { claim: 0 }), 300, 100, vec![]).unwrap();
+            reward_vault::claim_sub_epoch(Context::new(&crate::ID, &mut accounts, &[], ClaimSubEpochBumps { claim: 0 }), 300, 200, vec![]).unwrap();
+            assert_eq!(accounts.claim.claimed, 300);
+            assert_eq!(accounts.sub_epoch.total_disbursed, 300);
+        }
+        assert_eq!(recipient_info.lamports(), 600);
+    }
+
+
+    #[test]
+    fn vouchers_follow_the_payout_signer_rules() {
+        stub_syscalls();
+        let reward_vault = test_vault(|vault| vault.distributor = Pubkey::new_unique());
+        assert_eq!(authorize_voucher(&reward_vault, None, None, None).unwrap(), reward_vault.distributor);
+
+        // An epoch's own distributor replaces the vault's
+        let mut epoch = test_epoch(reward_vault.key(), NOW, NOW + 100);
+        let epoch_distributor = Pubkey::new_unique();
+        epoch.epoch_distributor = Some(epoch_distributor);
+        assert_eq!(authorize_voucher(&reward_vault, Some(&epoch), None, None).unwrap(), epoch_distributor);
+
+        // A live session key of that distributor signs in its place
+        let session_key = SessionKey {
+            vault: reward_vault.key(),
+            distributor: epoch_distributor,
+            session: Pubkey::new_unique(),
+            expires_at: NOW + 10,
+            bump: 0,
+        };
+        let session_key = Account::try_from(program_account(Pubkey::new_unique(), &session_key, SessionKey::LEN)).unwrap();
+        assert_eq!(
+            authorize_voucher(&reward_vault, Some(&epoch), Some(&session_key), None).unwrap(),
+            session_key.session
+        );
+        assert_eq!(
+            authorize_voucher(&reward_vault, None, Some(&session_key), None).unwrap_err(),
+            RewardVaultError::UnauthorizedDistributor.into()
+        );
+
+        let cosigned = test_vault(|vault| vault.require_admin_cosign = true);
+        assert_eq!(
+            authorize_voucher(&cosigned, None, None, None).unwrap_err(),
+            RewardVaultError::MissingAdminCosignature.into()
+        );
+        let paused = test_vault(|vault| vault.paused = true);
+        assert_eq!(
+            authorize_voucher(&paused, None, None, None).unwrap_err(),
+            RewardVaultError::VaultPaused.into()
+        );
+    }
+
+
+    #[test]
+    fn claim_many_finishes_partial_claims() {
+        stub_syscalls();
+        let rent_minimum = Rent::default().minimum_balance(RewardVault::LEN);
+        let reward_vault = test_vault(|vault| vault.pay_sol = true);
+        **reward_vault.to_account_info().try_borrow_mut_lamports().unwrap() = rent_minimum + 1_000;
+        let recipient_info = leak_account(Pubkey::new_unique(), System::id(), true, vec![]);
+        let recipient = recipient_info.key();
+
+        // A one-leaf tree: the root is the leaf itself
+        let mut epoch: Epoch = zeroed(Epoch::LEN);
+        epoch.vault = reward_vault.key();
+        epoch.end_ts = NOW + 100;
+        epoch.claim_root = claim_leaf(epoch.index, recipient, 300);
+        let epoch_info = program_account(Pubkey::new_unique(), &epoch, Epoch::LEN);
+
+        // A third of the allocation was already claimed on its own
+        let (claim_key, bump) = Pubkey::find_program_address(
+            &[Claim::SEED, epoch_info.key.as_ref(), recipient.as_ref()],
+            &crate::ID,
+        );
+        let claim = Claim {
+            epoch: epoch_info.key(),
+            recipient,
+            amount: 300,
+            claimed: 100,
+            bump,
+        };
+        let claim_info = program_account(claim_key, &claim, Claim::LEN);
+
+        let mut accounts = ClaimMany {
+            recipient: Signer::try_from(recipient_info).unwrap(),
+            reward_vault: reward_vault.clone(),
+            vault_token_account: None,
+            recipient_token_account: None,
+            token_program: token_program(),
+            system_program: system_program(),
+            acknowledgment: None,
+            blocklist: None,
+            audit_log: None,
+            vault_stats: None,
+        };
+        let remaining: &'static [AccountInfo] = Box::leak(Box::new([epoch_info.clone(), claim_info.clone()]));
+        let claims = || vec![EpochClaim { epoch_index: 0, amount: 300, proof: vec![] }];
+
+        reward_vault::claim_many(Context::new(&crate::ID, &mut accounts, remaining, ClaimManyBumps {}), claims()).unwrap();
+        assert_eq!(recipient_info.lamports(), 200);
+        let claim = Claim::try_read(claim_info).unwrap().unwrap();
+        assert_eq!(claim.claimed, 300);
+
+        assert_eq!(
+            reward_vault::claim_many(Context::new(&crate::ID, &mut accounts, remaining, ClaimManyBumps {}), claims()).unwrap_err(),
+            RewardVaultError::AlreadyClaimed.into()
+        );
+
+        // The batch is bounded by claims, not by the accounts they span
+        let full = vec![EpochClaim { epoch_index: 0, amount: 300, proof: vec![] }; RewardVault::MAX_BATCH + 1];
+        let spanned = (0..full.len()).flat_map(|_| remaining.to_vec()).collect::<Vec<_>>().leak();
+        assert_eq!(
+            reward_vault::claim_many(Context::new(&crate::ID, &mut accounts, spanned, ClaimManyBumps {}), full).unwrap_err(),
+            RewardVaultError::BatchTooLarge.into()
+        );
+    }
+
+
+    #[test]
+    fn sol_batches_follow_the_epoch_and_recipient_rules() {
+        stub_syscalls();
+        let rent_minimum = Rent::default().minimum_balance(RewardVault::LEN);
+        let distributor = leak_account(Pubkey::new_unique(), System::id(), true, vec![]);
+        let reward_vault = test_vault(|vault| {
+            vault.pay_sol = true;
+            vault.distributor = distributor.key();
+            vault.max_disburse_bps = RewardVault::FULL_DISBURSE_BPS;
+            vault.recipient_cooldown = 60;
+        });
+        **reward_vault.to_account_info().try_borrow_mut_lamports().unwrap() = rent_minimum + 1_000;
+        let mut epoch = test_epoch(reward_vault.key(), NOW - 100, NOW + 100);
+        epoch.reward_multiplier_bps = Epoch::MULTIPLIER_SCALE;
+
+        // Each recipient comes with its stats PDA, paid once already
+        let recipient_pair = |last_received_ts: i64| {
+            let mut recipient = leak_account(Pubkey::new_unique(), System::id(), false, vec![]).clone();
+            recipient.is_writable = true;
+            let (stats_key, bump) = Pubkey::find_program_address(
+                &[RecipientStats::SEED, reward_vault.key().as_ref(), recipient.key.as_ref()],
+                &crate::ID,
+            );
+            let stats = RecipientStats {
+                vault: reward_vault.key(),
+                recipient: recipient.key(),
+                lifetime_received: 10,
+                last_received_ts,
+                bump,
+            };
+            [recipient, program_account(stats_key, &stats, RecipientStats::LEN).clone()]
+        };
+        let ready: &'static [AccountInfo] = Box::leak([recipient_pair(NOW - 60), recipient_pair(NOW - 600)].concat().into_boxed_slice());
+        let cooling: &'static [AccountInfo] = Box::leak([recipient_pair(NOW - 600), recipient_pair(NOW - 59)].concat().into_boxed_slice());
+
+        let mut accounts = DisburseSolBatch {
+            reward_vault: reward_vault.clone(),
+            distributor_signer: distributor.clone(),
+            session_key: None,
+            admin_signer: None,
+            blocklist: None,
+            audit_log: None,
+            epoch: Some(epoch),
+            treasury: None,
+            payer: Signer::try_from(distributor).unwrap(),
+            system_program: system_program(),
+            vault_stats: None,
+        };
+
+        assert_eq!(
+            reward_vault::disburse_sol_batch(Context::new(&crate::ID, &mut accounts, cooling, DisburseSolBatchBumps {}), vec![100, 200], vec![]).unwrap_err(),
+            RewardVaultError::RecipientCooldownActive.into()
+        );
+        assert_eq!(
+            reward_vault::disburse_sol_batch(Context::new(&crate::ID, &mut accounts, &ready[..2], DisburseSolBatchBumps {}), vec![100, 200], vec![]).unwrap_err(),
+            RewardVaultError::BatchLengthMismatch.into()
+        );
+
+        reward_vault::disburse_sol_batch(Context::new(&crate::ID, &mut accounts, ready, DisburseSolBatchBumps {}), vec![100, 200], vec![]).unwrap();
+        assert_eq!(ready[0].lamports(), 100);
+        assert_eq!(ready[2].lamports(), 200);
+        assert_eq!(accounts.epoch.as_ref().unwrap().total_disbursed, 300);
+        let stats = Account::<RecipientStats>::try_from(&ready[3]).unwrap();
+        assert_eq!(stats.lifetime_received, 210);
+        assert_eq!(stats.last_received_ts, NOW);
+
+        // An epoch that has ended no longer pays out in batches either
+        accounts.epoch.as_mut().unwrap().end_ts = NOW;
+        accounts.reward_vault.recipient_cooldown = 0;
+        assert_eq!(
+            reward_vault::disburse_sol_batch(Context::new(&crate::ID, &mut accounts, ready, DisburseSolBatchBumps {}), vec![100, 200], vec![]).unwrap_err(),
+            RewardVaultError::EpochNotActive.into()
+        );
+    }
+
+
+    #[test]
+    fn split_counts_the_boosted_total_against_the_epoch() {
+        stub_syscalls();
+        let rent_minimum = Rent::default().minimum_balance(RewardVault::LEN);
+        let distributor = leak_account(Pubkey::new_unique(), System::id(), true, vec![]);
+        let reward_vault = test_vault(|vault| {
+            vault.pay_sol = true;
+            vault.distributor = distributor.key();
+            vault.max_disburse_bps = RewardVault::FULL_DISBURSE_BPS;
+        });
+        **reward_vault.to_account_info().try_borrow_mut_lamports().unwrap() = rent_minimum + 1_000;
+        let mut epoch = test_epoch(reward_vault.key(), NOW - 100, NOW + 100);
+        epoch.reward_multiplier_bps = Epoch::MULTIPLIER_SCALE * 2;
+        epoch.total_funded = 1_000;
+
+        let mut remaining = Vec::new();
+        for _ in 0..2 {
+            let mut recipient = leak_account(Pubkey::new_unique(), System::id(), false, vec![]).clone();
+            recipient.is_writable = true;
+            let (stats_key, bump) = Pubkey::find_program_address(
+                &[RecipientStats::SEED, reward_vault.key().as_ref(), recipient.key.as_ref()],
+                &crate::ID,
+            );
+            let stats = RecipientStats {
+                vault: reward_vault.key(),
+                recipient: recipient.key(),
+                lifetime_received: 0,
+                last_received_ts: 0,
+                bump,
+            };
+            remaining.push(recipient);
+            remaining.push(program_account(stats_key, &stats, RecipientStats::LEN).clone());
+        }
+        let remaining: &'static [AccountInfo] = remaining.leak();
+
+        let mut accounts = DisburseSolBatch {
+            reward_vault,
+            distributor_signer: distributor.clone(),
+            session_key: None,
+            admin_signer: None,
+            blocklist: None,
+            audit_log: None,
+            epoch: Some(epoch),
+            treasury: None,
+            payer: Signer::try_from(distributor).unwrap(),
+            system_program: system_program(),
+            vault_stats: None,
+        };
+
+        // The doubled total is split 1:2, with the dust on the last share
+        reward_vault::disburse_split(Context::new(&crate::ID, &mut accounts, remaining, DisburseSolBatchBumps {}), 151, vec![1, 2], vec![]).unwrap();
+        assert_eq!(remaining[0].lamports(), 100);
+        assert_eq!(remaining[2].lamports(), 202);
+        assert_eq!(accounts.epoch.as_ref().unwrap().total_disbursed, 302);
+        assert_eq!(Account::<RecipientStats>::try_from(&remaining[3]).unwrap().lifetime_received, 202);
+    }
+
+
+    #[test]
+    fn funding_records_what_the_vault_received() {
+        use anchor_spl::token_2022::spl_token_2022::state::{Account as TokenState, AccountState, Mint as MintState};
+
+        stub_syscalls();
+        let funder = leak_account(Pubkey::new_unique(), System::id(), true, vec![]);
+        let mint_key = Pubkey::new_unique();
+        let reward_vault = test_vault(|vault| vault.reward_mint = Some(mint_key));
+        let epoch = test_epoch(reward_vault.key(), NOW, NOW + 100);
+
+        // Token-2022 accounts for the mint, the funder, and the vault
+        let token_2022 = anchor_spl::token_2022::ID;
+        let mut mint = vec![0u8; MintState::LEN];
+        let mint_state = MintState {
+            decimals: 6,
+            is_initialized: true,
+            ..MintState::default()
+        };
+        MintState::pack(mint_state, &mut mint).unwrap();
+        let token_account = |owner: Pubkey, amount: u64| {
+            let mut data = vec![0u8; TokenState::LEN];
+            let state = TokenState {
+                mint: mint_key,
+                owner,
+                amount,
+                state: AccountState::Initialized,
+                ..TokenState::default()
+            };
+            TokenState::pack(state, &mut data).unwrap();
+            data
+        };
+        let vault_token_key = get_associated_token_address_with_program_id(&reward_vault.key(), &mint_key, &token_2022);
+        let funder_token = leak_account(Pubkey::new_unique(), token_2022, false, token_account(funder.key(), 10_000));
+        let vault_token = leak_account(vault_token_key, token_2022, false, token_account(reward_vault.key(), 500));
+
+        // Rent sysvar layout: lamports per byte-year, exemption threshold, burn percent
+        let rent = Rent::default();
+        let mut rent_data = rent.lamports_per_byte_year.to_le_bytes().to_vec();
+        rent_data.extend_from_slice(&rent.exemption_threshold.to_le_bytes());
+        rent_data.push(rent.burn_percent);
+        let rent_info = leak_account(anchor_lang::solana_program::sysvar::rent::ID, anchor_lang::solana_program::sysvar::ID, false, rent_data);
+
+        let mut accounts = FundVault {
+            reward_vault,
+            funder: Signer::try_from(funder).unwrap(),
+            epoch: Some(epoch),
+            funder_token_account: Some(InterfaceAccount::try_from(funder_token).unwrap()),
+            vault_token_account: Some(InterfaceAccount::try_from(vault_token).unwrap()),
+            mint: Some(InterfaceAccount::try_from(leak_account(mint_key, token_2022, false, mint)).unwrap()),
+            funder_contribution: None,
+            fund_receipt: None,
+            token_program: Interface::try_from(executable_account(token_2022)).unwrap(),
+            associated_token_program: Program::try_from(executable_account(AssociatedToken::id())).unwrap(),
+            system_program: system_program(),
+            rent: Sysvar::from_account_info(rent_info).unwrap(),
+            vault_stats: None,
+        };
+
+        // The mint withholds 1% of the 2,000 sent, so the epoch counts 1,980
+        reward_vault::fund_vault(Context::new(&crate::ID, &mut accounts, &[], FundVaultBumps {}), 2_000, vec![], None).unwrap();
+        assert_eq!(accounts.epoch.as_ref().unwrap().total_funded, 1_980);
+        assert_eq!(TokenState::unpack(&vault_token.try_borrow_data().unwrap()).unwrap().amount, 2_480);
+        assert_eq!(TokenState::unpack(&funder_token.try_borrow_data().unwrap()).unwrap().amount, 8_000);
+    }
+
+
+    #[test]
+    fn scaled_amount_uses_the_payout_decimals() {
+        let spl_vault = test_vault(|vault| vault.mint_decimals = 6);
+        assert_eq!(scale_amount(&spl_vault, 1_234_567).unwrap(), (1, 234_567, 6));
+        assert_eq!(scale_amount(&spl_vault, 999_999).unwrap(), (0, 999_999, 6));
+
+        let sol_vault = test_vault(|vault| vault.pay_sol = true);
+        assert_eq!(scale_amount(&sol_vault, 2_500_000_000).unwrap(), (2, 500_000_000, 9));
+        assert_eq!(scale_amount(&sol_vault, 1_234_567).unwrap(), (0, 1_234_567, 9));
+    }
+
+
+    #[test]
+    fn withdrawn_contributions_leave_the_funding_totals() {
+        stub_syscalls();
+        let reward_vault = test_vault(|vault| {
+            vault.pay_sol = true;
+            vault.stats_active = true;
+        });
+        let rent_minimum = Rent::default().minimum_balance(RewardVault::LEN);
+        **reward_vault.to_account_info().try_borrow_mut_lamports().unwrap() = rent_minimum + 1_000;
+
+        let mut epoch = test_epoch(reward_vault.key(), NOW + 100, NOW + 200);
+        epoch.total_funded = 1_000;
+        let funder_info = leak_account(Pubkey::new_unique(), System::id(), true, vec![]);
+        let contribution = FunderContribution {
+            epoch: epoch.key(),
+            funder: funder_info.key(),
+            amount: 1_000,
+            bump: 0,
+        };
+        let mut vault_stats: VaultStats = zeroed(VaultStats::LEN);
+        vault_stats.vault = reward_vault.key();
+        vault_stats.total_funded_all_time = 1_000;
+
+        let mut accounts = WithdrawContribution {
+            funder: Signer::try_from(funder_info).unwrap(),
+            reward_vault,
+            epoch,
+            funder_contribution: Account::try_from(program_account(Pubkey::new_unique(), &contribution, FunderContribution::LEN)).unwrap(),
+            vault_token_account: None,
+            funder_token_account: None,
+            token_program: token_program(),
+            vault_stats: Some(Account::try_from(program_account(Pubkey::new_unique(), &vault_stats, VaultStats::LEN)).unwrap()),
+        };
+        reward_vault::withdraw_contribution(Context::new(&crate::ID, &mut accounts, &[], WithdrawContributionBumps {}), 300).unwrap();
+        assert_eq!(accounts.funder.lamports(), 300);
+        assert_eq!(accounts.epoch.total_funded, 700);
+        assert_eq!(accounts.funder_contribution.amount, 700);
+        assert_eq!(accounts.vault_stats.as_ref().unwrap().total_funded_all_time, 700);
+    }
+
+
+    #[test]
+    fn refunds_come_out_of_the_payout_totals() {
+        let mut recipient_stats: RecipientStats = zeroed(RecipientStats::LEN);
+        recipient_stats.lifetime_received = 500;
+        recipient_stats.record_refund(200);
+        assert_eq!(recipient_stats.lifetime_received, 300);
+
+        let mut vault_stats: VaultStats = zeroed(VaultStats::LEN);
+        vault_stats.record_disbursements(500, 2).unwrap();
+        vault_stats.record_refund(200);
+        assert_eq!(vault_stats.total_disbursed_all_time, 300);
+        assert_eq!(vault_stats.disbursement_count, 2);
+
+        // Payouts made before stats were enabled saturate at zero
+        vault_stats.record_refund(1_000);
+        assert_eq!(vault_stats.total_disbursed_all_time, 0);
+    }
 }